@@ -1,16 +1,32 @@
 //! Procedural macro helpers for `citro3d`.
+//!
+//! Macros which run devkitPro tools (`picasso` and `tex3ds`) report the tool
+//! path, command line, and run time of each invocation as compiler warnings
+//! when the `CITRO3D_MACROS_VERBOSE` environment variable is set to a
+//! non-empty value. This helps to debug `DEVKITPRO` path and toolchain issues,
+//! which otherwise only show up as opaque macro errors. Since the variable is
+//! read at expansion time, crates using the macros may need to be rebuilt
+//! (e.g. with `cargo clean -p <crate>`) for it to take effect.
 
 // we're already nightly-only so might as well use unstable proc macro APIs.
 #![feature(proc_macro_span)]
+#![feature(proc_macro_expand)]
+#![feature(proc_macro_diagnostic)]
 
 use std::error::Error;
-use std::fs::DirBuilder;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::fs::{self, DirBuilder};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use std::{env, process};
 
 use litrs::StringLit;
-use proc_macro::TokenStream;
-use quote::quote;
+use proc_macro::{Span, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{Data, DataStruct, DeriveInput, Fields};
+
+mod shbin;
 
 /// Compiles the given PICA200 shader using [`picasso`](https://github.com/devkitPro/picasso)
 /// and returns the compiled bytes directly as a `&[u8]` slice.
@@ -20,10 +36,12 @@ use quote::quote;
 ///
 /// The compiled shader binary will be saved in the caller's `$OUT_DIR`.
 ///
-/// # Errors
+/// Like `include_bytes!`, the input is expanded before use, so the path may be
+/// computed at compile time with macros like [`concat!`] or [`env!`].
 ///
-/// This macro will fail to compile if the input is not a single string literal.
-/// In other words, inputs like `concat!("foo", "/bar")` are not supported.
+/// An optional second argument can be used to pass command-line options to
+/// `picasso`, separated by whitespace. This allows building several variants
+/// of the same shader source, each of which is saved to a separate output file.
 ///
 /// # Example
 ///
@@ -31,12 +49,14 @@ use quote::quote;
 /// use citro3d_macros::include_shader;
 ///
 /// static SHADER_BYTES: &[u8] = include_shader!("../tests/integration.pica");
+/// static CONCAT_BYTES: &[u8] = include_shader!(concat!("../tests/", "integration.pica"));
+/// static NO_NOP_BYTES: &[u8] = include_shader!("../tests/integration.pica", "--no-nop");
 /// ```
 ///
 /// # Errors
 ///
-/// The macro will fail to compile if the `.pica` file cannot be found, or contains
-/// `picasso` syntax errors.
+/// The macro will fail to compile if the arguments do not expand to string
+/// literals, the `.pica` file cannot be found, or it contains `picasso` syntax errors.
 ///
 /// ```compile_fail
 /// # use citro3d_macros::include_shader;
@@ -59,19 +79,707 @@ pub fn include_shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 }
 
 fn include_shader_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>> {
-    let tokens: Vec<_> = input.into_iter().collect();
+    let mut args = split_args(input).into_iter();
+
+    let (Some(path_arg), options_arg, None) = (args.next(), args.next(), args.next()) else {
+        return Err("expected a shader source path and optional `picasso` options".into());
+    };
+
+    let shader_source_file = resolve_input_path(path_arg)?;
+    let options = options_arg.map(expand_string_arg).transpose()?;
+    let options: Vec<&str> = options
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect();
+
+    let out_path = shader_output_path(&shader_source_file, &options)?;
+    let bytes = run_picasso(&shader_source_file, &out_path, &options)?;
+
+    let source_file_path = shader_source_file.to_string_lossy();
+    let aligned_bytes = aligned_bytes(&bytes);
+
+    let result = quote! {
+        {
+            // ensure the source is re-evaluted if the input file changes
+            const _SOURCE: &[u8] = include_bytes! ( #source_file_path );
+
+            #aligned_bytes
+        }
+    };
+
+    Ok(result.into())
+}
+
+/// Compiles every PICA200 shader (`.pica` file) in the given directory and its
+/// subdirectories, returning a `&[(&str, &[u8])]` slice mapping each shader's
+/// name to its compiled bytes.
+///
+/// The name of each shader is its path relative to the given directory, without
+/// the `.pica` extension and with `/` as the path separator. The resulting
+/// slice is sorted by name, so it can be searched with
+/// [`binary_search_by_key`](slice::binary_search_by_key).
+///
+/// The directory path is resolved the same way as for [`include_shader!`], and
+/// each shader binary is compiled and aligned the same way as well.
+///
+/// Modifying any of the included shaders will cause them to be recompiled, but
+/// adding or removing files from the directory will not be detected until the
+/// invoking crate is rebuilt for some other reason.
+///
+/// # Example
+///
+/// ```
+/// use citro3d_macros::include_shaders_dir;
+///
+/// static SHADERS: &[(&str, &[u8])] = include_shaders_dir!("../tests/shaders");
+///
+/// let idx = SHADERS
+///     .binary_search_by_key(&"passthrough", |&(name, _)| name)
+///     .unwrap();
+/// let (_, passthrough) = SHADERS[idx];
+/// assert_eq!(passthrough.len() % 4, 0);
+/// ```
+///
+/// # Errors
+///
+/// The macro will fail to compile if the input does not expand to a single string
+/// literal, the directory cannot be read, or any of its `.pica` files contain
+/// `picasso` syntax errors.
+///
+/// ```compile_fail
+/// # use citro3d_macros::include_shaders_dir;
+/// static _ERROR: &[(&str, &[u8])] = include_shaders_dir!("../tests/nonexistent");
+/// ```
+#[proc_macro]
+pub fn include_shaders_dir(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match include_shaders_dir_impl(input) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            let err_str = err.to_string();
+            quote! { compile_error!( #err_str ) }.into()
+        }
+    }
+}
+
+fn include_shaders_dir_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>> {
+    let shader_dir = resolve_input_path(input)?;
+
+    let mut shader_sources = Vec::new();
+    find_shader_sources(&shader_dir, &mut shader_sources)?;
+    shader_sources.sort();
+
+    let mut names = Vec::new();
+    let mut shaders = Vec::new();
+
+    for shader_source_file in &shader_sources {
+        // UNWRAP: every source was found by walking `shader_dir`
+        let relative = shader_source_file.strip_prefix(&shader_dir).unwrap();
+        let name = relative
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let out_path = shader_output_path(shader_source_file, &[])?;
+        let bytes = run_picasso(shader_source_file, &out_path, &[])?;
+        let source_file_path = shader_source_file.to_string_lossy();
+        let aligned_bytes = aligned_bytes(&bytes);
+
+        names.push(name);
+        shaders.push(quote! {
+            {
+                // ensure the source is re-evaluted if the input file changes
+                const _SOURCE: &[u8] = include_bytes! ( #source_file_path );
+
+                #aligned_bytes
+            }
+        });
+    }
+
+    let result = quote! {
+        {
+            const SHADERS: &[(&str, &[u8])] = &[ #( ( #names, #shaders ) ),* ];
+
+            SHADERS
+        }
+    };
+
+    Ok(result.into())
+}
+
+/// Compiles PICA200 shader source code given directly as a string, and returns
+/// the compiled bytes as a `&[u8]` slice. This is useful for small shaders that
+/// don't warrant their own `.pica` file.
+///
+/// The source is written to a file in the caller's `$OUT_DIR` before compiling,
+/// so any errors reported by `picasso` will refer to that file. The compiled
+/// bytes are aligned the same way as for [`include_shader!`].
+///
+/// An optional second argument can be used to pass command-line options to
+/// `picasso`, the same as for [`include_shader!`].
+///
+/// # Example
+///
+/// ```
+/// use citro3d_macros::compile_shader;
+///
+/// static SHADER_BYTES: &[u8] = compile_shader!(
+///     r#"
+///     .out outpos position
+///     .out outclr color
+///
+///     .proc main
+///         mov outpos, v0
+///         mov outclr, v1
+///         end
+///     .end
+///     "#
+/// );
+/// ```
+///
+/// # Errors
+///
+/// The macro will fail to compile if the arguments do not expand to string
+/// literals, or the source contains `picasso` syntax errors.
+///
+/// ```compile_fail
+/// # use citro3d_macros::compile_shader;
+/// static _ERROR: &[u8] = compile_shader!("not a shader");
+/// ```
+#[proc_macro]
+pub fn compile_shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match compile_shader_impl(input) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            let err_str = err.to_string();
+            quote! { compile_error!( #err_str ) }.into()
+        }
+    }
+}
+
+fn compile_shader_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>> {
+    let mut args = split_args(input).into_iter();
+
+    let (Some(source_arg), options_arg, None) = (args.next(), args.next(), args.next()) else {
+        return Err("expected shader source code and optional `picasso` options".into());
+    };
+
+    let source = expand_string_arg(source_arg)?;
+    let options = options_arg.map(expand_string_arg).transpose()?;
+    let options: Vec<&str> = options
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect();
+
+    // Name the source file by its contents, so identical shaders share a file
+    // and different ones never overwrite each other.
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    options.hash(&mut hasher);
+
+    let source_dir = PathBuf::from(env!("OUT_DIR")).join("inline-shaders");
+    DirBuilder::new()
+        .recursive(true)
+        .create(&source_dir)
+        .map_err(|err| format!("unable to create output directory {source_dir:?}: {err}"))?;
+
+    let shader_source_file = source_dir.join(format!("{:016x}.pica", hasher.finish()));
+    fs::write(&shader_source_file, &source)
+        .map_err(|err| format!("unable to write shader source {shader_source_file:?}: {err}"))?;
+
+    let out_path = shader_source_file.with_extension("shbin");
+    let bytes = run_picasso(&shader_source_file, &out_path, &options)?;
+
+    Ok(aligned_bytes(&bytes).into())
+}
+
+/// Generates strongly typed uniform bindings for a PICA200 shader, so that
+/// uniforms can be bound without looking up their indices by name at runtime.
+///
+/// This attribute is applied to a unit struct, and takes the same arguments as
+/// [`include_shader!`]. The shader is compiled, and the struct is given one
+/// public field for each uniform declared by the shader, plus a `bind` method
+/// which binds every field to its uniform in the appropriate shader stage.
+///
+/// Field names are converted to `snake_case`, and field types depend on the
+/// type and size of each uniform:
+///
+/// | Uniform         | Field type               |
+/// |-----------------|--------------------------|
+/// | `.fvec name`    | `citro3d::math::FVec4`   |
+/// | `.fvec name[2]` | `[FVec4; 2]`             |
+/// | `.fvec name[3]` | `[FVec4; 3]`             |
+/// | `.fvec name[4]` | `citro3d::math::Matrix4` |
+/// | `.fvec name[N]` | `[FVec4; N]`             |
+/// | `.ivec name`    | `citro3d::math::IVec`    |
+/// | `.bool name`    | `bool`                   |
+///
+/// Because the generated code refers to the `citro3d` crate, this macro should
+/// be used via the re-export in `citro3d::macros`.
+///
+/// # Example
+///
+/// ```ignore
+/// use citro3d::macros::shader_uniforms;
+///
+/// // Given a shader declaring `.fvec projection[4]`
+/// #[shader_uniforms("assets/vshader.pica")]
+/// struct Uniforms;
+///
+/// let uniforms = Uniforms {
+///     projection: Matrix4::identity(),
+/// };
+/// uniforms.bind(&mut instance);
+/// ```
+///
+/// # Errors
+///
+/// The macro will fail to compile for all the same reasons as [`include_shader!`],
+/// or if it is applied to anything other than a unit struct.
+#[proc_macro_attribute]
+pub fn shader_uniforms(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    match shader_uniforms_impl(attr, item) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            let err_str = err.to_string();
+            quote! { compile_error!( #err_str ); }.into()
+        }
+    }
+}
+
+fn shader_uniforms_impl(
+    attr: TokenStream,
+    item: TokenStream,
+) -> Result<TokenStream, Box<dyn Error>> {
+    let DeriveInput {
+        attrs,
+        vis,
+        ident,
+        generics,
+        data,
+    } = syn::parse(item)?;
+
+    let is_unit_struct = matches!(
+        data,
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        })
+    );
+
+    if !is_unit_struct || !generics.params.is_empty() {
+        return Err("`shader_uniforms` can only be applied to a unit struct".into());
+    }
+
+    let mut args = split_args(attr).into_iter();
+
+    let (Some(path_arg), options_arg, None) = (args.next(), args.next(), args.next()) else {
+        return Err("expected a shader source path and optional `picasso` options".into());
+    };
+
+    let shader_source_file = resolve_input_path(path_arg)?;
+    let options = options_arg.map(expand_string_arg).transpose()?;
+    let options: Vec<&str> = options
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect();
+
+    let out_path = shader_output_path(&shader_source_file, &options)?;
+    let bytes = run_picasso(&shader_source_file, &out_path, &options)?;
+
+    // Uniforms may be shared between the vertex and geometry shader, in which
+    // case they get a single field that is bound to both stages.
+    let mut uniforms: Vec<(shbin::UniformEntry, Vec<shbin::ShaderType>)> = Vec::new();
+
+    for entrypoint in shbin::parse(&bytes)? {
+        for uniform in entrypoint.uniforms {
+            match uniforms.iter_mut().find(|(u, _)| u.name == uniform.name) {
+                Some((existing, stages)) => {
+                    if (existing.start, existing.count) != (uniform.start, uniform.count) {
+                        return Err(format!(
+                            "uniform `{}` uses different registers in different entrypoints",
+                            uniform.name
+                        )
+                        .into());
+                    }
+                    if !stages.contains(&entrypoint.ty) {
+                        stages.push(entrypoint.ty);
+                    }
+                }
+                None => uniforms.push((uniform, vec![entrypoint.ty])),
+            }
+        }
+    }
+
+    let mut fields = Vec::new();
+    let mut binds = Vec::new();
+
+    for (uniform, stages) in &uniforms {
+        let field = format_ident!("{}", to_snake_case(&uniform.name));
+        let (ty, is_array) = match (uniform.kind(), uniform.count) {
+            (shbin::UniformKind::Float, 1) => (quote!(::citro3d::math::FVec4), false),
+            (shbin::UniformKind::Float, 4) => (quote!(::citro3d::math::Matrix4), false),
+            (shbin::UniformKind::Float, n @ (2 | 3)) => {
+                let n = usize::from(n);
+                (quote!([::citro3d::math::FVec4; #n]), false)
+            }
+            (shbin::UniformKind::Float, n) => {
+                let n = usize::from(n);
+                (quote!([::citro3d::math::FVec4; #n]), true)
+            }
+            (shbin::UniformKind::Int, 1) => (quote!(::citro3d::math::IVec), false),
+            (shbin::UniformKind::Int, n) => {
+                let n = usize::from(n);
+                (quote!([::citro3d::math::IVec; #n]), true)
+            }
+            (shbin::UniformKind::Bool, 1) => (quote!(bool), false),
+            (shbin::UniformKind::Bool, n) => {
+                let n = usize::from(n);
+                (quote!([bool; #n]), true)
+            }
+        };
+
+        let doc = format!("The `{}` uniform.", uniform.name);
+        fields.push(quote! {
+            #[doc = #doc]
+            pub #field: #ty
+        });
+
+        let start = uniform.start;
+        for stage in stages {
+            let bind_fn = match stage {
+                shbin::ShaderType::Vertex => format_ident!("bind_vertex_uniform"),
+                shbin::ShaderType::Geometry => format_ident!("bind_geometry_uniform"),
+            };
+
+            binds.push(if is_array {
+                quote! {
+                    for (offset, value) in (0u8..).zip(self.#field) {
+                        instance.#bind_fn(::citro3d::uniform::Index::from(#start + offset), value);
+                    }
+                }
+            } else {
+                quote! {
+                    instance.#bind_fn(::citro3d::uniform::Index::from(#start), self.#field);
+                }
+            });
+        }
+    }
+
+    let source_file_path = shader_source_file.to_string_lossy();
+
+    let result = quote! {
+        #(#attrs)*
+        #vis struct #ident {
+            #(#fields,)*
+        }
+
+        impl #ident {
+            /// Bind every uniform in this struct to its shader stage(s).
+            #[allow(unused_variables)]
+            pub fn bind(&self, instance: &mut ::citro3d::Instance) {
+                // ensure the bindings are regenerated if the input file changes
+                const _SOURCE: &[u8] = include_bytes! ( #source_file_path );
+
+                #(#binds)*
+            }
+        }
+    };
+
+    Ok(result.into())
+}
+
+/// Convert a shader symbol name like `modelView` to `model_view`.
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut prev_lower = false;
+
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && prev_lower {
+            result.push('_');
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        result.push(c.to_ascii_lowercase());
+    }
+
+    result
+}
+
+/// Derives `citro3d::attrib::VertexAttributes` for a `#[repr(C)]` vertex struct,
+/// registering one attribute per field in declaration order. See the trait
+/// documentation for details.
+///
+/// # Errors
+///
+/// The derive will fail to compile if it is applied to anything other than a
+/// struct, or the struct is not `#[repr(C)]` (other hints such as
+/// `#[repr(C, align(16))]` are allowed alongside it).
+///
+/// ```compile_fail
+/// # // Stand in for the parts of `citro3d` the generated code refers to, so
+/// # // the only error is the missing `#[repr(C)]`.
+/// # extern crate self as citro3d;
+/// # pub type Result<T> = std::result::Result<T, ()>;
+/// # pub mod attrib {
+/// #     pub enum Format { Float }
+/// #     pub trait Attribute { const FORMAT: Format; const COUNT: u8; }
+/// #     impl Attribute for [f32; 3] { const FORMAT: Format = Format::Float; const COUNT: u8 = 3; }
+/// #     pub struct Register;
+/// #     impl Register { pub fn new(_: u16) -> crate::Result<Self> { Ok(Self) } }
+/// #     pub struct Info;
+/// #     impl Info {
+/// #         pub fn new() -> Self { Self }
+/// #         pub fn add_loader(&mut self, _: Register, _: Format, _: u8) -> crate::Result<()> { Ok(()) }
+/// #     }
+/// #     pub trait VertexAttributes { fn attrib_info() -> crate::Result<Info>; }
+/// # }
+/// use citro3d_macros::VertexAttributes;
+///
+/// #[derive(VertexAttributes)]
+/// struct Vertex {
+///     position: [f32; 3],
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_derive(VertexAttributes)]
+pub fn derive_vertex_attributes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match derive_vertex_attributes_impl(input) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            let err_str = err.to_string();
+            quote! { compile_error!( #err_str ); }.into()
+        }
+    }
+}
+
+fn derive_vertex_attributes_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>> {
+    let DeriveInput {
+        attrs,
+        ident,
+        generics,
+        data,
+        ..
+    } = syn::parse(input)?;
+
+    let Data::Struct(DataStruct { fields, .. }) = data else {
+        return Err("`VertexAttributes` can only be derived for structs".into());
+    };
+
+    if !has_repr_c(&attrs)? {
+        return Err("`VertexAttributes` can only be derived for `#[repr(C)]` structs".into());
+    }
+
+    let field_types = fields.iter().map(|field| &field.ty);
+    let registers = 0..u16::try_from(fields.len())?;
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let result = quote! {
+        impl #impl_generics ::citro3d::attrib::VertexAttributes for #ident #ty_generics #where_clause {
+            fn attrib_info() -> ::citro3d::Result<::citro3d::attrib::Info> {
+                let mut info = ::citro3d::attrib::Info::new();
+
+                #(
+                    info.add_loader(
+                        ::citro3d::attrib::Register::new(#registers)?,
+                        <#field_types as ::citro3d::attrib::Attribute>::FORMAT,
+                        <#field_types as ::citro3d::attrib::Attribute>::COUNT,
+                    )?;
+                )*
+
+                Ok(info)
+            }
+        }
+    };
+
+    Ok(result.into())
+}
+
+/// Whether the given attributes include `#[repr(C)]`, possibly alongside other
+/// representation hints like `align(16)`.
+fn has_repr_c(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut is_repr_c = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            is_repr_c |= meta.path.is_ident("C");
+
+            // Skip the arguments of hints like `align(16)` or `packed(2)`
+            if meta.input.peek(syn::token::Paren) {
+                let args;
+                syn::parenthesized!(args in meta.input);
+                args.parse::<proc_macro2::TokenStream>()?;
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(is_repr_c)
+}
+
+/// Converts the given image using [`tex3ds`](https://github.com/devkitPro/tex3ds)
+/// and returns the resulting `.t3x` bytes directly as a `&[u8]` slice.
+///
+/// The image path is resolved the same way as for [`include_shader!`], and the
+/// output `.t3x` file will be saved in the caller's `$OUT_DIR`. The resulting
+/// bytes are aligned to 4 bytes.
+///
+/// An optional second argument can be used to pass command-line options to
+/// `tex3ds`, separated by whitespace, such as the texture format (`-f`) or
+/// mipmap filter (`-m`). See the `tex3ds` documentation for all available options.
+///
+/// # Example
+///
+/// ```
+/// use citro3d_macros::include_texture;
+///
+/// static TEXTURE_BYTES: &[u8] = include_texture!("../tests/texture.png");
+/// static RGB565_BYTES: &[u8] = include_texture!("../tests/texture.png", "-f rgb565 -m bilinear");
+/// ```
+///
+/// # Errors
+///
+/// The macro will fail to compile if the arguments do not expand to string
+/// literals, the image cannot be found, or `tex3ds` fails to convert it.
+///
+/// ```compile_fail
+/// # use citro3d_macros::include_texture;
+/// static _ERROR: &[u8] = include_texture!("../tests/nonexistent.png");
+/// ```
+#[proc_macro]
+pub fn include_texture(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match include_texture_impl(input) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            let err_str = err.to_string();
+            quote! { compile_error!( #err_str ) }.into()
+        }
+    }
+}
+
+fn include_texture_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>> {
+    let mut args = split_args(input).into_iter();
+
+    let (Some(path_arg), options_arg, None) = (args.next(), args.next(), args.next()) else {
+        return Err("expected an image path and optional `tex3ds` options".into());
+    };
+
+    let image_source_file = resolve_input_path(path_arg)?;
+    let options = options_arg.map(expand_string_arg).transpose()?;
+
+    let out_path = output_path(&image_source_file, "t3x")?;
+
+    let mut tool_args: Vec<&OsStr> = options
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(OsStr::new)
+        .collect();
+    tool_args.extend([
+        OsStr::new("-o"),
+        out_path.as_os_str(),
+        image_source_file.as_os_str(),
+    ]);
+
+    run_devkitpro_tool("tex3ds", tool_args)
+        .map_err(|err| format!("failed to convert texture: {err}"))?;
+
+    let bytes = fs::read(&out_path)
+        .map_err(|err| format!("unable to read output file {out_path:?}: {err}"))?;
+
+    let source_file_path = image_source_file.to_string_lossy();
+    let aligned_bytes = aligned_bytes(&bytes);
+
+    let result = quote! {
+        {
+            // ensure the texture is re-converted if the input file changes
+            const _SOURCE: &[u8] = include_bytes! ( #source_file_path );
+
+            #aligned_bytes
+        }
+    };
+
+    Ok(result.into())
+}
+
+/// Recursively collect the paths of all `.pica` files in `dir`.
+fn find_shader_sources(dir: &Path, sources: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let entries =
+        fs::read_dir(dir).map_err(|err| format!("unable to read directory {dir:?}: {err}"))?;
+
+    for entry in entries {
+        let path = entry
+            .map_err(|err| format!("unable to read directory {dir:?}: {err}"))?
+            .path();
+
+        if path.is_dir() {
+            find_shader_sources(&path, sources)?;
+        } else if path.extension().is_some_and(|ext| ext == "pica") {
+            sources.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Split macro input into its comma-separated arguments. A trailing comma is
+/// allowed, as with most built-in macros.
+fn split_args(input: TokenStream) -> Vec<TokenStream> {
+    let mut args = Vec::new();
+    let mut current = Vec::new();
+
+    for token in input {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                args.push(current.drain(..).collect());
+            }
+            _ => current.push(token),
+        }
+    }
+
+    if !current.is_empty() {
+        args.push(current.into_iter().collect());
+    }
+
+    args
+}
+
+/// Expand a single macro argument, which must evaluate to a string literal,
+/// and return its value. The argument may use macros like `concat!` or `env!`.
+fn expand_string_arg(arg: TokenStream) -> Result<String, Box<dyn Error>> {
+    let expanded = arg
+        .expand_expr()
+        .map_err(|err| format!("unable to expand macro argument: {err}"))?;
+
+    let tokens: Vec<_> = expanded.into_iter().collect();
 
     if tokens.len() != 1 {
         return Err(format!("expected exactly one input token, got {}", tokens.len()).into());
     }
 
-    let shader_source_filename = &tokens[0];
+    let string_lit = StringLit::try_from(&tokens[0]).map_err(|err| err.to_string())?;
+
+    Ok(string_lit.value().to_owned())
+}
 
-    let string_lit = match StringLit::try_from(shader_source_filename) {
-        Ok(lit) => lit,
-        Err(err) => return Ok(err.to_compile_error()),
+/// Resolve the path given as a macro argument relative to the invoking source
+/// file, the same way `include_bytes!` does. The argument is expanded first, so
+/// it may use macros like `concat!` or `env!`.
+fn resolve_input_path(arg: TokenStream) -> Result<PathBuf, Box<dyn Error>> {
+    // Keep the span of the original input, since expanded tokens may not point
+    // back to the invoking source file.
+    let Some(invocation_token) = arg.clone().into_iter().next() else {
+        return Err("expected a path, got no input".into());
     };
 
+    let input_path = expand_string_arg(arg)?;
+
     // The cwd can change depending on whether this is running in a doctest or not:
     // https://users.rust-lang.org/t/which-directory-does-a-proc-macro-run-from/71917
     //
@@ -79,34 +787,110 @@ fn include_shader_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>
     let cwd = env::current_dir()
         .map_err(|err| format!("unable to determine current directory: {err}"))?;
 
-    let invoking_source_file = shader_source_filename.span().source_file().path();
+    let invoking_source_file = invocation_token.span().source_file().path();
     let Some(invoking_source_dir) = invoking_source_file.parent() else {
-        return Ok(quote! {
-            compile_error!(
-                concat!(
-                    "unable to find parent directory of current source file \"",
-                    file!(),
-                    "\""
-                )
-            )
-        }
+        return Err(format!(
+            "unable to find parent directory of current source file {invoking_source_file:?}"
+        )
         .into());
     };
 
     // By joining these three pieces, we arrive at approximately the same behavior as `include_bytes!`
-    let shader_source_file = cwd
+    let path = cwd
         .join(invoking_source_dir)
-        .join(string_lit.value())
+        .join(input_path)
         // This might be overkill, but it ensures we get a unique path if different
         // shaders with the same relative path are used within one program
         .canonicalize()
-        .map_err(|err| format!("unable to resolve absolute path of shader source: {err}"))?;
+        .map_err(|err| format!("unable to resolve absolute path of input: {err}"))?;
+
+    Ok(path)
+}
+
+/// Get the path in `$OUT_DIR` to save the compiled output of the given shader
+/// source file with the given `picasso` options.
+fn shader_output_path(
+    shader_source_file: &Path,
+    options: &[&str],
+) -> Result<PathBuf, Box<dyn Error>> {
+    let extension = if options.is_empty() {
+        String::from("shbin")
+    } else {
+        // Different options may produce different output for the same source,
+        // so keep each variant in its own file.
+        let mut hasher = DefaultHasher::new();
+        options.hash(&mut hasher);
+        format!("{:016x}.shbin", hasher.finish())
+    };
+
+    output_path(shader_source_file, &extension)
+}
+
+/// Compile the given shader source with `picasso` and any extra `options`,
+/// saving the output to `out_path` and returning the compiled bytes.
+///
+/// Compilation is skipped if `out_path` was already compiled from the same
+/// source contents, options, and `picasso` executable. Note that files included
+/// by the shader source are not considered.
+fn run_picasso(
+    shader_source_file: &Path,
+    out_path: &Path,
+    options: &[&str],
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let source = fs::read(shader_source_file)
+        .map_err(|err| format!("unable to read shader source {shader_source_file:?}: {err}"))?;
+
+    // The executable's size and modification time stand in for its version,
+    // so the cache is invalidated when devkitPro tools are updated.
+    let picasso = devkitpro_tool_path("picasso");
+    let picasso_metadata =
+        fs::metadata(&picasso).map_err(|err| format!("unable to find {picasso:?}: {err}"))?;
+
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    options.hash(&mut hasher);
+    picasso_metadata.len().hash(&mut hasher);
+    picasso_metadata.modified().ok().hash(&mut hasher);
+    let cache_key = format!("{:016x}", hasher.finish());
+
+    let mut cache_file = out_path.as_os_str().to_owned();
+    cache_file.push(".hash");
+    let cache_file = PathBuf::from(cache_file);
 
-    let shader_out_file: PathBuf = shader_source_file.with_extension("shbin");
+    if fs::read_to_string(&cache_file).is_ok_and(|key| key == cache_key) {
+        if let Ok(bytes) = fs::read(out_path) {
+            log_verbose(format!("picasso: using cached output {out_path:?}"));
+            return Ok(bytes);
+        }
+    }
+
+    let mut tool_args: Vec<&OsStr> = options.iter().map(OsStr::new).collect();
+    tool_args.extend([
+        OsStr::new("--out"),
+        out_path.as_os_str(),
+        shader_source_file.as_os_str(),
+    ]);
+
+    run_devkitpro_tool("picasso", tool_args)
+        .map_err(|err| format!("failed to compile shader: {err}"))?;
+
+    let bytes = fs::read(out_path)
+        .map_err(|err| format!("unable to read output file {out_path:?}: {err}"))?;
+
+    // Failing to write the cache key only means recompiling next time.
+    let _ = fs::write(&cache_file, cache_key);
+
+    Ok(bytes)
+}
+
+/// Get the path in `$OUT_DIR` to write the output for a given (canonicalized)
+/// source file, creating its parent directories if needed.
+fn output_path(source_file: &Path, extension: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let out_file: PathBuf = source_file.with_extension(extension);
 
     let out_dir = PathBuf::from(env!("OUT_DIR"));
 
-    let out_path = out_dir.join(shader_out_file.components().skip(1).collect::<PathBuf>());
+    let out_path = out_dir.join(out_file.components().skip(1).collect::<PathBuf>());
     // UNWRAP: we already canonicalized the source path, so it should have a parent.
     let out_parent = out_path.parent().unwrap();
 
@@ -115,14 +899,37 @@ fn include_shader_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>
         .create(out_parent)
         .map_err(|err| format!("unable to create output directory {out_parent:?}: {err}"))?;
 
+    Ok(out_path)
+}
+
+/// Get the path of one of the tools in `$DEVKITPRO/tools/bin`.
+fn devkitpro_tool_path(tool: &str) -> PathBuf {
     let devkitpro = PathBuf::from(env!("DEVKITPRO"));
-    let picasso = devkitpro.join("tools/bin/picasso");
+    devkitpro.join("tools/bin").join(tool)
+}
+
+/// Run one of the tools in `$DEVKITPRO/tools/bin` with the given arguments,
+/// returning an error if it fails.
+fn run_devkitpro_tool<I, S>(tool: &str, args: I) -> Result<(), Box<dyn Error>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let tool_path = devkitpro_tool_path(tool);
+
+    let mut command = process::Command::new(&tool_path);
+    command.args(args);
 
-    let output = process::Command::new(&picasso)
-        .arg("--out")
-        .args([&out_path, &shader_source_file])
+    let start = Instant::now();
+    let output = command
         .output()
-        .map_err(|err| format!("unable to run {picasso:?}: {err}"))?;
+        .map_err(|err| format!("unable to run {tool_path:?}: {err}"))?;
+
+    log_verbose(format!(
+        "{tool}: ran {command:?} in {:.2?} ({})",
+        start.elapsed(),
+        output.status,
+    ));
 
     let error_code = match output.status.code() {
         Some(0) => None,
@@ -131,22 +938,27 @@ fn include_shader_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>
 
     if let Some(code) = error_code {
         return Err(format!(
-            "failed to compile shader: `picasso` exited with status {code}: {}",
+            "`{tool}` exited with status {code}: {}",
             String::from_utf8_lossy(&output.stderr),
         )
         .into());
     }
 
-    let bytes = std::fs::read(&out_path)
-        .map_err(|err| format!("unable to read output file {out_path:?}: {err}"))?;
+    Ok(())
+}
 
-    let source_file_path = shader_source_file.to_string_lossy();
+/// Emit `message` as a warning at the macro call site, if the
+/// `CITRO3D_MACROS_VERBOSE` environment variable is set to a non-empty value.
+fn log_verbose(message: String) {
+    if env::var_os("CITRO3D_MACROS_VERBOSE").is_some_and(|var| !var.is_empty()) {
+        Span::call_site().warning(message).emit();
+    }
+}
 
-    let result = quote! {
+/// Emit an expression evaluating to a `&[u8]` containing `bytes`, aligned to 4 bytes.
+fn aligned_bytes(bytes: &[u8]) -> proc_macro2::TokenStream {
+    quote! {
         {
-            // ensure the source is re-evaluted if the input file changes
-            const _SOURCE: &[u8] = include_bytes! ( #source_file_path );
-
             // https://users.rust-lang.org/t/can-i-conveniently-compile-bytes-into-a-rust-program-with-a-specific-alignment/24049/2
             #[repr(C)]
             struct AlignedAsU32<Bytes: ?Sized> {
@@ -163,7 +975,46 @@ fn include_shader_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>
 
             &ALIGNED.bytes
         }
-    };
+    }
+}
 
-    Ok(result.into())
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn snake_case_names() {
+        assert_eq!(to_snake_case("modelView"), "model_view");
+        assert_eq!(to_snake_case("projection"), "projection");
+        assert_eq!(to_snake_case("lightPos0"), "light_pos0");
+        assert_eq!(to_snake_case("light0Color"), "light0_color");
+        assert_eq!(to_snake_case("MVP"), "mvp");
+        assert_eq!(to_snake_case("already_snake"), "already_snake");
+    }
+
+    #[test]
+    fn repr_c_with_other_hints() {
+        let aligned: syn::Attribute = parse_quote!(#[repr(C, align(16))]);
+        assert!(has_repr_c(&[aligned]).unwrap());
+
+        let packed: syn::Attribute = parse_quote!(#[repr(C, packed(2))]);
+        assert!(has_repr_c(&[packed]).unwrap());
+
+        let separate: Vec<syn::Attribute> =
+            vec![parse_quote!(#[repr(align(4))]), parse_quote!(#[repr(C)])];
+        assert!(has_repr_c(&separate).unwrap());
+    }
+
+    #[test]
+    fn missing_repr_c() {
+        assert!(!has_repr_c(&[]).unwrap());
+
+        let rust: syn::Attribute = parse_quote!(#[repr(align(16))]);
+        assert!(!has_repr_c(&[rust]).unwrap());
+
+        let other: syn::Attribute = parse_quote!(#[derive(Clone)]);
+        assert!(!has_repr_c(&[other]).unwrap());
+    }
 }
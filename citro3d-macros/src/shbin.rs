@@ -0,0 +1,236 @@
+//! A minimal parser for compiled shader binaries (`.shbin` files), used to
+//! inspect shaders at compile time. See <https://www.3dbrew.org/wiki/SHBIN>
+//! for details of the format.
+
+use std::error::Error;
+
+/// The type of shader an entrypoint (DVLE) contains.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderType {
+    Vertex,
+    Geometry,
+}
+
+/// A parsed shader entrypoint (DVLE).
+#[derive(Debug)]
+pub struct Entrypoint {
+    pub ty: ShaderType,
+    pub uniforms: Vec<UniformEntry>,
+}
+
+/// An entry in a DVLE's uniform table.
+#[derive(Debug)]
+pub struct UniformEntry {
+    pub name: String,
+    /// The first register used by the uniform, in the same numbering as
+    /// `shaderInstanceGetUniformLocation` (i.e. excluding input registers).
+    pub start: u8,
+    /// The number of registers used by the uniform.
+    pub count: u8,
+}
+
+/// The kind of register a uniform is stored in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UniformKind {
+    Float,
+    Int,
+    Bool,
+}
+
+impl UniformEntry {
+    pub fn kind(&self) -> UniformKind {
+        // Registers from https://www.3dbrew.org/wiki/SHBIN#Uniform_Table_Entry,
+        // with the 0x10 input registers excluded.
+        match self.start {
+            0x00..=0x5F => UniformKind::Float,
+            0x60..=0x67 => UniformKind::Int,
+            _ => UniformKind::Bool,
+        }
+    }
+}
+
+const DVLB_MAGIC: &[u8] = b"DVLB";
+const DVLE_MAGIC: &[u8] = b"DVLE";
+
+// The uniform registers are numbered after the 16 input registers.
+const FIRST_UNIFORM_REGISTER: u16 = 0x10;
+
+/// Parse all the entrypoints of a compiled shader binary.
+pub fn parse(bytes: &[u8]) -> Result<Vec<Entrypoint>, Box<dyn Error>> {
+    if bytes.get(..4) != Some(DVLB_MAGIC) {
+        return Err("shader binary does not start with DVLB magic".into());
+    }
+
+    let dvle_count = read_u32(bytes, 0x4)?;
+
+    (0..dvle_count)
+        .map(|i| {
+            let dvle_offset = read_u32(bytes, 0x8 + 4 * i as usize)?;
+            let dvle = bytes
+                .get(dvle_offset as usize..)
+                .ok_or("DVLE offset out of bounds")?;
+            parse_dvle(dvle)
+        })
+        .collect()
+}
+
+fn parse_dvle(dvle: &[u8]) -> Result<Entrypoint, Box<dyn Error>> {
+    if dvle.get(..4) != Some(DVLE_MAGIC) {
+        return Err("shader entrypoint does not start with DVLE magic".into());
+    }
+
+    let ty = match dvle.get(0x6) {
+        Some(0) => ShaderType::Vertex,
+        Some(1) => ShaderType::Geometry,
+        other => return Err(format!("unknown shader type {other:?}").into()),
+    };
+
+    let uniform_table_offset = read_u32(dvle, 0x30)? as usize;
+    let uniform_count = read_u32(dvle, 0x34)? as usize;
+    let symbol_table_offset = read_u32(dvle, 0x38)? as usize;
+
+    let symbols = dvle
+        .get(symbol_table_offset..)
+        .ok_or("symbol table offset out of bounds")?;
+
+    let uniforms = (0..uniform_count)
+        .map(|i| {
+            let entry = uniform_table_offset + 8 * i;
+            let symbol_offset = read_u32(dvle, entry)? as usize;
+            let start_reg = read_u16(dvle, entry + 4)?;
+            let end_reg = read_u16(dvle, entry + 6)?;
+
+            let name = symbols
+                .get(symbol_offset..)
+                .and_then(|s| s.split(|&b| b == 0).next())
+                .ok_or("uniform symbol offset out of bounds")?;
+
+            let start = start_reg
+                .checked_sub(FIRST_UNIFORM_REGISTER)
+                .ok_or("uniform uses an input register")?;
+            let count = end_reg
+                .checked_sub(start_reg)
+                .ok_or("uniform register range is reversed")?
+                + 1;
+
+            Ok(UniformEntry {
+                name: String::from_utf8(name.to_vec())?,
+                start: start.try_into()?,
+                count: count.try_into()?,
+            })
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    Ok(Entrypoint { ty, uniforms })
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, Box<dyn Error>> {
+    let field = bytes
+        .get(offset..offset + 4)
+        .ok_or("unexpected end of shader binary")?;
+    Ok(u32::from_le_bytes(field.try_into()?))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, Box<dyn Error>> {
+    let field = bytes
+        .get(offset..offset + 2)
+        .ok_or("unexpected end of shader binary")?;
+    Ok(u16::from_le_bytes(field.try_into()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a shader binary with a single vertex shader DVLE containing the
+    /// given uniforms, as `(name, start register, end register)`.
+    fn build(uniforms: &[(&str, u16, u16)]) -> Vec<u8> {
+        const DVLE_OFFSET: usize = 0xC;
+        const DVLE_HEADER_SIZE: usize = 0x40;
+
+        let mut bytes = DVLB_MAGIC.to_vec();
+        bytes.extend(1_u32.to_le_bytes());
+        bytes.extend((DVLE_OFFSET as u32).to_le_bytes());
+
+        let mut dvle = vec![0; DVLE_HEADER_SIZE];
+        dvle[..4].copy_from_slice(DVLE_MAGIC);
+
+        let uniform_table_offset = dvle.len();
+        let mut symbols = Vec::new();
+        for &(name, start, end) in uniforms {
+            dvle.extend((symbols.len() as u32).to_le_bytes());
+            dvle.extend(start.to_le_bytes());
+            dvle.extend(end.to_le_bytes());
+            symbols.extend(name.as_bytes());
+            symbols.push(0);
+        }
+        let symbol_table_offset = dvle.len();
+        dvle.extend(symbols);
+
+        dvle[0x30..0x34].copy_from_slice(&(uniform_table_offset as u32).to_le_bytes());
+        dvle[0x34..0x38].copy_from_slice(&(uniforms.len() as u32).to_le_bytes());
+        dvle[0x38..0x3C].copy_from_slice(&(symbol_table_offset as u32).to_le_bytes());
+
+        bytes.extend(dvle);
+        bytes
+    }
+
+    #[test]
+    fn parses_uniforms() {
+        let entrypoints = parse(&build(&[
+            ("projection", 0x10, 0x13),
+            ("count", 0x70, 0x70),
+            ("enabled", 0x78, 0x78),
+        ]))
+        .unwrap();
+
+        let [entrypoint] = &entrypoints[..] else {
+            panic!("expected one entrypoint, got {entrypoints:?}");
+        };
+        assert_eq!(entrypoint.ty, ShaderType::Vertex);
+
+        let uniforms: Vec<_> = entrypoint
+            .uniforms
+            .iter()
+            .map(|u| (u.name.as_str(), u.start, u.count, u.kind()))
+            .collect();
+        assert_eq!(
+            uniforms,
+            [
+                ("projection", 0x00, 4, UniformKind::Float),
+                ("count", 0x60, 1, UniformKind::Int),
+                ("enabled", 0x68, 1, UniformKind::Bool),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = build(&[]);
+        bytes[..4].copy_from_slice(b"DVLX");
+        assert!(parse(&bytes).is_err());
+
+        let mut bytes = build(&[]);
+        bytes[0xC..0x10].copy_from_slice(b"DVLX");
+        assert!(parse(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_table() {
+        let bytes = build(&[("projection", 0x10, 0x13)]);
+
+        // Cut the binary off partway through the uniform table entry
+        let truncated = &bytes[..0xC + 0x40 + 6];
+        assert!(parse(truncated).is_err());
+
+        // Or before the DVLE offsets
+        assert!(parse(&bytes[..6]).is_err());
+    }
+
+    #[test]
+    fn rejects_uniform_in_input_register() {
+        let bytes = build(&[("position", 0x00, 0x00)]);
+        let err = parse(&bytes).unwrap_err();
+        assert!(err.to_string().contains("input register"), "{err}");
+    }
+}
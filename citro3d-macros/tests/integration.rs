@@ -1,4 +1,4 @@
-use citro3d_macros::include_shader;
+use citro3d_macros::{compile_shader, include_shader, include_shaders_dir, include_texture};
 
 #[test]
 fn includes_shader_static() {
@@ -13,3 +13,55 @@ fn includes_shader_const() {
 
     assert_eq!(SHADER_BYTES.len() % 4, 0);
 }
+
+#[test]
+fn includes_shader_concat() {
+    static SHADER_BYTES: &[u8] = include_shader!(concat!("integration", ".pica"));
+
+    assert_eq!(SHADER_BYTES.len() % 4, 0);
+}
+
+#[test]
+fn includes_shaders_dir() {
+    static SHADERS: &[(&str, &[u8])] = include_shaders_dir!("shaders");
+
+    let names: Vec<_> = SHADERS.iter().map(|&(name, _)| name).collect();
+    assert_eq!(names, ["nested/color", "passthrough"]);
+
+    for (_, bytes) in SHADERS {
+        assert_eq!(bytes.len() % 4, 0);
+    }
+}
+
+#[test]
+fn includes_texture() {
+    static TEXTURE_BYTES: &[u8] = include_texture!("texture.png", "-f rgba8");
+
+    assert!(!TEXTURE_BYTES.is_empty());
+    assert_eq!(TEXTURE_BYTES.as_ptr().align_offset(4), 0);
+}
+
+#[test]
+fn includes_shader_with_options() {
+    static SHADER_BYTES: &[u8] = include_shader!("integration.pica", "--no-nop");
+
+    assert_eq!(SHADER_BYTES.len() % 4, 0);
+}
+
+#[test]
+fn compiles_inline_shader() {
+    static SHADER_BYTES: &[u8] = compile_shader!(
+        r#"
+        .out outpos position
+        .out outclr color
+
+        .proc main
+            mov outpos, v0
+            mov outclr, v1
+            end
+        .end
+        "#
+    );
+
+    assert_eq!(SHADER_BYTES.len() % 4, 0);
+}
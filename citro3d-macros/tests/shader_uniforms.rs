@@ -0,0 +1,100 @@
+//! Tests for `#[shader_uniforms]`. The generated code refers to `::citro3d`, so
+//! this crate stands in for it with types that record each bind.
+
+extern crate self as citro3d;
+
+use citro3d_macros::shader_uniforms;
+
+pub mod math {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct FVec4(pub f32);
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct Matrix4(pub f32);
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct IVec(pub i32);
+}
+
+pub mod uniform {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct Index(pub u8);
+
+    impl From<u8> for Index {
+        fn from(value: u8) -> Self {
+            Self(value)
+        }
+    }
+}
+
+/// A value bound to a uniform register.
+#[derive(Debug, PartialEq)]
+pub enum Bound {
+    Float(math::FVec4),
+    Matrix(math::Matrix4),
+    Int(math::IVec),
+    Bool(bool),
+}
+
+impl From<math::FVec4> for Bound {
+    fn from(value: math::FVec4) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<math::Matrix4> for Bound {
+    fn from(value: math::Matrix4) -> Self {
+        Self::Matrix(value)
+    }
+}
+
+impl From<math::IVec> for Bound {
+    fn from(value: math::IVec) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<bool> for Bound {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+#[derive(Default)]
+pub struct Instance {
+    binds: Vec<(u8, Bound)>,
+}
+
+impl Instance {
+    pub fn bind_vertex_uniform(&mut self, index: uniform::Index, value: impl Into<Bound>) {
+        self.binds.push((index.0, value.into()));
+    }
+}
+
+#[shader_uniforms("uniforms.pica")]
+struct Uniforms;
+
+#[test]
+fn binds_each_uniform_kind() {
+    // Also checks the generated field names and types
+    let uniforms = Uniforms {
+        projection: math::Matrix4(1.0),
+        tint: math::FVec4(2.0),
+        loop_count: math::IVec(3),
+        use_tint: true,
+    };
+
+    let mut instance = Instance::default();
+    uniforms.bind(&mut instance);
+    instance.binds.sort_by_key(|&(index, _)| index);
+
+    assert_eq!(
+        instance.binds,
+        [
+            (0x00, Bound::Matrix(math::Matrix4(1.0))),
+            (0x04, Bound::Float(math::FVec4(2.0))),
+            (0x60, Bound::Int(math::IVec(3))),
+            (0x68, Bound::Bool(true)),
+        ]
+    );
+}
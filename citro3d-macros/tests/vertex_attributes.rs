@@ -0,0 +1,83 @@
+//! Tests for `#[derive(VertexAttributes)]`. The generated code refers to
+//! `::citro3d`, so this crate stands in for it with a minimal `attrib` module.
+
+extern crate self as citro3d;
+
+use citro3d_macros::VertexAttributes;
+
+#[derive(Debug)]
+pub struct Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub mod attrib {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Format {
+        Byte,
+        Float,
+    }
+
+    pub trait Attribute {
+        const FORMAT: Format;
+        const COUNT: u8;
+    }
+
+    impl Attribute for [f32; 3] {
+        const FORMAT: Format = Format::Float;
+        const COUNT: u8 = 3;
+    }
+
+    impl Attribute for [u8; 4] {
+        const FORMAT: Format = Format::Byte;
+        const COUNT: u8 = 4;
+    }
+
+    pub struct Register(u16);
+
+    impl Register {
+        pub fn new(index: u16) -> crate::Result<Self> {
+            Ok(Self(index))
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Info {
+        pub loaders: Vec<(u16, Format, u8)>,
+    }
+
+    impl Info {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn add_loader(
+            &mut self,
+            register: Register,
+            format: Format,
+            count: u8,
+        ) -> crate::Result<()> {
+            self.loaders.push((register.0, format, count));
+            Ok(())
+        }
+    }
+
+    pub trait VertexAttributes {
+        fn attrib_info() -> crate::Result<Info>;
+    }
+}
+
+use attrib::{Format, VertexAttributes};
+
+#[allow(dead_code)] // only the layout is used
+#[derive(VertexAttributes)]
+#[repr(C, align(16))]
+struct AlignedVertex {
+    position: [f32; 3],
+    color: [u8; 4],
+}
+
+#[test]
+fn derives_with_other_repr_hints() {
+    let info = AlignedVertex::attrib_info().unwrap();
+    assert_eq!(info.loaders, [(0, Format::Float, 3), (1, Format::Byte, 4)]);
+}
@@ -1,15 +1,9 @@
 //! This example demonstrates the most basic usage of `citro3d`: rendering a simple
 //! RGB triangle (sometimes called a "Hello triangle") to the 3DS screen.
 
-#![feature(allocator_api)]
-
-use citro3d::macros::include_shader;
-use citro3d::math::{AspectRatio, ClipPlanes, Matrix4, Projection, StereoDisplacement};
-use citro3d::render::ClearFlags;
-use citro3d::texenv;
-use citro3d::{attrib, buffer, render, shader};
+use citro3d::attrib;
+use citro3d::prelude::*;
 use ctru::prelude::*;
-use ctru::services::gfx::{RawFrameBuffer, Screen, TopScreen3D};
 
 #[repr(C)]
 #[derive(Copy, Clone)]
@@ -25,8 +19,13 @@ impl Vec3 {
     }
 }
 
+impl attrib::Attribute for Vec3 {
+    const FORMAT: attrib::Format = attrib::Format::Float;
+    const COUNT: u8 = 3;
+}
+
 #[repr(C)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, VertexAttributes)]
 struct Vertex {
     pos: Vec3,
     color: Vec3,
@@ -48,6 +47,10 @@ static VERTICES: &[Vertex] = &[
 ];
 
 static SHADER_BYTES: &[u8] = include_shader!("assets/vshader.pica");
+
+#[shader_uniforms("assets/vshader.pica")]
+struct Uniforms;
+
 const CLEAR_COLOR: u32 = 0x68_B0_D8_FF;
 
 fn main() {
@@ -60,26 +63,18 @@ fn main() {
 
     let mut instance = citro3d::Instance::new().expect("failed to initialize Citro3D");
 
-    let top_screen = TopScreen3D::from(&gfx.top_screen);
+    let ScreenTargets {
+        top_left,
+        top_right,
+        bottom,
+    } = instance
+        .screen_targets(&gfx, None)
+        .expect("failed to create render targets");
 
-    let (mut top_left, mut top_right) = top_screen.split_mut();
-
-    let RawFrameBuffer { width, height, .. } = top_left.raw_framebuffer();
-    let mut top_left_target = instance
-        .render_target(width, height, top_left, None)
-        .expect("failed to create render target");
-
-    let RawFrameBuffer { width, height, .. } = top_right.raw_framebuffer();
-    let mut top_right_target = instance
-        .render_target(width, height, top_right, None)
-        .expect("failed to create render target");
-
-    let mut bottom_screen = gfx.bottom_screen.borrow_mut();
-    let RawFrameBuffer { width, height, .. } = bottom_screen.raw_framebuffer();
-
-    let mut bottom_target = instance
-        .render_target(width, height, bottom_screen, None)
-        .expect("failed to create bottom screen render target");
+    // Each target is cleared the first time it's selected in a frame
+    let clear = ClearSpec::new(ClearFlags::ALL, CLEAR_COLOR, 0);
+    let [top_left_target, top_right_target, bottom_target] =
+        [top_left, top_right, bottom].map(|target| target.with_default_clear(clear));
 
     let shader = shader::Library::from_bytes(SHADER_BYTES).unwrap();
     let vertex_shader = shader.get(0).unwrap();
@@ -87,21 +82,19 @@ fn main() {
     let program = shader::Program::new(vertex_shader).unwrap();
     instance.bind_program(&program);
 
-    let mut vbo_data = Vec::with_capacity_in(VERTICES.len(), ctru::linear::LinearAllocator);
-    vbo_data.extend_from_slice(VERTICES);
-
-    let mut buf_info = buffer::Info::new();
-    let (attr_info, vbo_data) = prepare_vbos(&mut buf_info, &vbo_data);
+    let vbo_data = Buffer::from_slice(VERTICES).unwrap();
+    let mesh = Mesh::new(vbo_data, Primitive::Triangles).unwrap();
 
-    // Configure the first fragment shading substage to just pass through the vertex color
+    // Configure the first fragment shading substage to just pass through the vertex color,
+    // leaving the other stages to pass through its output.
     // See https://www.opengl.org/sdk/docs/man2/xhtml/glTexEnv.xml for more insight
     let stage0 = texenv::Stage::new(0).unwrap();
-    instance
-        .texenv(stage0)
-        .src(texenv::Mode::BOTH, texenv::Source::PrimaryColor, None, None)
-        .func(texenv::Mode::BOTH, texenv::CombineFunc::Replace);
-
-    let projection_uniform_idx = program.get_uniform("projection").unwrap();
+    instance.set_texenvs(|texenvs| {
+        texenvs
+            .stage(stage0)
+            .src(texenv::Mode::BOTH, texenv::Source::PrimaryColor, None, None)
+            .func(texenv::Mode::BOTH, texenv::CombineFunc::Replace);
+    });
 
     while apt.main_loop() {
         hid.scan_input();
@@ -111,67 +104,39 @@ fn main() {
         }
 
         instance.render_frame_with(|instance| {
-            let mut render_to = |target: &mut render::Target, projection| {
-                target.clear(ClearFlags::ALL, CLEAR_COLOR, 0);
-
+            let mut render_to = |target: &Target, projection: Matrix4| {
                 instance
                     .select_render_target(target)
                     .expect("failed to set render target");
 
-                instance.bind_vertex_uniform(projection_uniform_idx, projection);
-
-                instance.set_attr_info(&attr_info);
+                Uniforms { projection }.bind(instance);
 
-                instance.draw_arrays(buffer::Primitive::Triangles, vbo_data);
+                instance.draw_mesh(&mesh).expect("failed to draw mesh");
             };
 
             let Projections {
                 left_eye,
                 right_eye,
                 center,
-            } = calculate_projections();
+            } = calculate_projections(instance.stereo_strength());
 
-            render_to(&mut top_left_target, &left_eye);
-            render_to(&mut top_right_target, &right_eye);
-            render_to(&mut bottom_target, &center);
+            render_to(&top_left_target, left_eye);
+            render_to(&top_right_target, right_eye);
+            render_to(&bottom_target, center);
         });
     }
 }
 
-fn prepare_vbos<'a>(
-    buf_info: &'a mut buffer::Info,
-    vbo_data: &'a [Vertex],
-) -> (attrib::Info, buffer::Slice<'a>) {
-    // Configure attributes for use with the vertex shader
-    let mut attr_info = attrib::Info::new();
-
-    let reg0 = attrib::Register::new(0).unwrap();
-    let reg1 = attrib::Register::new(1).unwrap();
-
-    attr_info
-        .add_loader(reg0, attrib::Format::Float, 3)
-        .unwrap();
-
-    attr_info
-        .add_loader(reg1, attrib::Format::Float, 3)
-        .unwrap();
-
-    let buf_idx = buf_info.add(vbo_data, &attr_info).unwrap();
-
-    (attr_info, buf_idx)
-}
-
 struct Projections {
     left_eye: Matrix4,
     right_eye: Matrix4,
     center: Matrix4,
 }
 
-fn calculate_projections() -> Projections {
+fn calculate_projections(stereo_strength: f32) -> Projections {
     // TODO: it would be cool to allow playing around with these parameters on
     // the fly with D-pad, etc.
-    let slider_val = ctru::os::current_3d_slider_state();
-    let interocular_distance = slider_val / 2.0;
+    let interocular_distance = stereo_strength / 2.0;
 
     let vertical_fov = 40.0_f32.to_radians();
     let screen_depth = 2.0;
@@ -0,0 +1,180 @@
+//! A per-frame arena allocator over LINEAR memory, for transient GPU data.
+//!
+//! Allocating a new [`Buffer`](crate::buffer::Buffer) every frame for dynamic
+//! geometry works, but repeatedly allocating and freeing LINEAR memory can
+//! fragment the (rather small) LINEAR heap over time. A [`FrameArena`] instead
+//! allocates a single block up front and hands out pieces of it, all of which
+//! are released at once at the start of the next frame.
+
+use std::alloc::{Allocator, Layout};
+use std::cell::Cell;
+use std::mem;
+use std::ptr::NonNull;
+
+use ctru::linear::LinearAllocator;
+
+use crate::buffer;
+use crate::memory::LiveCount;
+
+/// The alignment of the arena's backing allocation.
+const ARENA_ALIGN: usize = 0x80;
+
+/// A bump allocator over LINEAR memory, which is reset every frame by
+/// [`Instance::render_frame_with_arena`](crate::Instance::render_frame_with_arena).
+///
+/// Allocations borrow the arena, so they can't outlive the frame they were
+/// made in.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::arena::FrameArena;
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// let mut arena = FrameArena::new(0x1000).unwrap();
+///
+/// instance.render_frame_with_arena(&mut arena, |_instance, arena| {
+///     let vertices = arena.alloc_slice_copy(&[[0.0_f32; 3]; 3]).unwrap();
+///     assert_eq!(vertices.len(), 3);
+///     assert_eq!(arena.used(), 36);
+/// });
+/// ```
+#[derive(Debug)]
+pub struct FrameArena {
+    ptr: NonNull<u8>,
+    capacity: usize,
+    used: Cell<usize>,
+    _live: LiveCount,
+}
+
+impl FrameArena {
+    /// Allocate an arena with room for `capacity` bytes.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`](crate::Error::InvalidSize) if `capacity` is zero
+    /// * [`Error::OutOfMemory`](crate::Error::OutOfMemory) if there isn't enough
+    ///   LINEAR memory for the arena
+    #[doc(alias = "linearMemAlign")]
+    pub fn new(capacity: usize) -> crate::Result<Self> {
+        if capacity == 0 {
+            return Err(crate::Error::InvalidSize);
+        }
+
+        let ptr = LinearAllocator
+            .allocate(Self::layout(capacity)?)
+            .map_err(|_| crate::Error::OutOfMemory)?;
+
+        Ok(Self {
+            ptr: ptr.cast(),
+            capacity,
+            used: Cell::new(0),
+            _live: LiveCount::arena(),
+        })
+    }
+
+    /// The total size of the arena, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of bytes allocated so far this frame, including padding.
+    pub fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    /// Release every allocation made from the arena. This is done
+    /// automatically at the start of each frame rendered with
+    /// [`Instance::render_frame_with_arena`](crate::Instance::render_frame_with_arena).
+    ///
+    /// The GPU may still be reading from the arena if the last frame hasn't
+    /// finished rendering, so this should only be called after waiting for it.
+    pub fn reset(&mut self) {
+        self.used.set(0);
+    }
+
+    /// Allocate a copy of `data` in the arena, and flush it from the CPU cache
+    /// so the GPU can read it.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::OutOfMemory`](crate::Error::OutOfMemory) if the arena is full
+    /// * [`Error::System`](crate::Error::System) if the cache could not be
+    ///   flushed
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_copy<T: Copy>(&self, data: &[T]) -> crate::Result<&mut [T]> {
+        let ptr = self.alloc_raw::<T>(data.len())?;
+        // SAFETY: the allocation has room for exactly `data.len()` elements,
+        // and can't overlap `data` since it was just handed out by the arena.
+        let slice = unsafe {
+            ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
+            std::slice::from_raw_parts_mut(ptr, data.len())
+        };
+        buffer::flush_data_cache(slice)?;
+        Ok(slice)
+    }
+
+    /// Allocate `len` elements in the arena, initializing each one by calling
+    /// `f` with its index, and flush them from the CPU cache so the GPU can
+    /// read them.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::OutOfMemory`](crate::Error::OutOfMemory) if the arena is full
+    /// * [`Error::System`](crate::Error::System) if the cache could not be
+    ///   flushed
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_fill_with<T: Copy>(
+        &self,
+        len: usize,
+        mut f: impl FnMut(usize) -> T,
+    ) -> crate::Result<&mut [T]> {
+        let ptr = self.alloc_raw::<T>(len)?;
+        // SAFETY: the allocation has room for exactly `len` elements, each of
+        // which is initialized before the slice is created.
+        let slice = unsafe {
+            for i in 0..len {
+                ptr.add(i).write(f(i));
+            }
+            std::slice::from_raw_parts_mut(ptr, len)
+        };
+        buffer::flush_data_cache(slice)?;
+        Ok(slice)
+    }
+
+    /// Reserve room for `len` elements of type `T`. The returned pointer is
+    /// aligned for `T`, and the memory it points to is not used by any other
+    /// allocation until the arena is reset (which requires `&mut self`, so no
+    /// allocations can be alive at that point).
+    fn alloc_raw<T>(&self, len: usize) -> crate::Result<*mut T> {
+        let base = self.ptr.as_ptr() as usize;
+        let start = (base + self.used.get())
+            .checked_next_multiple_of(mem::align_of::<T>())
+            .ok_or(crate::Error::OutOfMemory)?
+            - base;
+        let end = mem::size_of::<T>()
+            .checked_mul(len)
+            .and_then(|size| start.checked_add(size))
+            .filter(|&end| end <= self.capacity)
+            .ok_or(crate::Error::OutOfMemory)?;
+
+        self.used.set(end);
+
+        // SAFETY: `start` is within the arena, as checked above
+        Ok(unsafe { self.ptr.as_ptr().add(start).cast() })
+    }
+
+    fn layout(capacity: usize) -> crate::Result<Layout> {
+        Layout::from_size_align(capacity, ARENA_ALIGN).map_err(|_| crate::Error::InvalidSize)
+    }
+}
+
+impl Drop for FrameArena {
+    #[doc(alias = "linearFree")]
+    fn drop(&mut self) {
+        if let Ok(layout) = Self::layout(self.capacity) {
+            // SAFETY: the pointer was allocated with the same allocator and layout
+            unsafe { LinearAllocator.deallocate(self.ptr, layout) };
+        }
+    }
+}
@@ -63,6 +63,81 @@ impl From<Format> for u8 {
     }
 }
 
+/// A type which can be used as a single vertex attribute, such as one field of
+/// a vertex struct. Implementors describe the [`Format`] and number of elements
+/// the GPU should read for the attribute.
+///
+/// This is implemented for the supported scalar types, and arrays of up to
+/// 4 of them.
+pub trait Attribute {
+    /// The data format of each element of the attribute.
+    const FORMAT: Format;
+    /// The number of elements in the attribute (up to 4).
+    const COUNT: u8;
+}
+
+macro_rules! impl_attribute {
+    ($($ty:ty => $format:ident),* $(,)?) => {
+        $(
+            impl Attribute for $ty {
+                const FORMAT: Format = Format::$format;
+                const COUNT: u8 = 1;
+            }
+
+            impl_attribute!(@array $ty => $format, 1, 2, 3, 4);
+        )*
+    };
+    (@array $ty:ty => $format:ident, $($n:literal),*) => {
+        $(
+            impl Attribute for [$ty; $n] {
+                const FORMAT: Format = Format::$format;
+                const COUNT: u8 = $n;
+            }
+        )*
+    };
+}
+
+impl_attribute! {
+    i8 => Byte,
+    u8 => UnsignedByte,
+    f32 => Float,
+    i16 => Short,
+}
+
+/// A vertex type which can describe its own attribute layout. This is usually
+/// implemented with `#[derive(VertexAttributes)]` from [`crate::macros`].
+///
+/// The derived implementation registers one attribute per field, in declaration
+/// order, with field `N` using input [`Register`] `N`. Every field type must
+/// implement [`Attribute`], and the struct should be `#[repr(C)]` without any
+/// padding between fields.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use citro3d::attrib::VertexAttributes;
+/// use citro3d::macros::VertexAttributes;
+///
+/// #[repr(C)]
+/// #[derive(VertexAttributes)]
+/// struct Vertex {
+///     pos: [f32; 3],
+///     color: [u8; 4],
+/// }
+///
+/// let attr_info = Vertex::attrib_info().unwrap();
+/// assert_eq!(attr_info.attr_count(), 2);
+/// ```
+pub trait VertexAttributes {
+    /// Build the attribute info describing this vertex type.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the type has too many attributes to register.
+    fn attrib_info() -> crate::Result<Info>;
+}
+
 // SAFETY: the RWLock ensures unique access when mutating the global struct, and
 // we trust citro3d to Do The Right Thing™ and not mutate it otherwise.
 unsafe impl Sync for Info {}
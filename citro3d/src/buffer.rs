@@ -3,9 +3,224 @@
 //! See the [`attrib`] module for details on how to describe the shape and type
 //! of the VBO data.
 
+use std::alloc::Allocator;
 use std::mem::MaybeUninit;
+use std::ops::{Deref, DerefMut};
+
+use ctru::linear::LinearAllocator;
 
 use crate::attrib;
+use crate::memory::LiveCount;
+
+/// The largest vertex stride (size of one `T`) supported by the GPU, in bytes.
+const MAX_STRIDE: usize = u8::MAX as usize;
+
+/// An allocator whose allocations can be read by the GPU, and so can back a
+/// [`Buffer`]. This is implemented for [`LinearAllocator`], and can be
+/// implemented for e.g. VRAM allocators or pools carved out of LINEAR memory.
+///
+/// # Safety
+///
+/// Every allocation made by the allocator must be in memory the GPU can
+/// access, i.e. with a physical address that `osConvertVirtToPhys` can
+/// resolve, such as the LINEAR heap or VRAM.
+pub unsafe trait GpuVisibleAlloc: Allocator {}
+
+// SAFETY: LINEAR memory is always accessible to the GPU
+unsafe impl GpuVisibleAlloc for LinearAllocator {}
+
+// SAFETY: a reference allocates from the same memory as the allocator itself
+unsafe impl<A: GpuVisibleAlloc + ?Sized> GpuVisibleAlloc for &A {}
+
+/// An owned vertex buffer object, allocated in memory which can be read by the
+/// GPU (LINEAR memory, unless another [`GpuVisibleAlloc`] is used). This
+/// dereferences to `[T]`, and can be registered with [`Info::add_buffer`].
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::buffer::Buffer;
+/// let vbo = Buffer::from_slice(&[[0.0_f32, 1.0, 2.0], [3.0, 4.0, 5.0]]).unwrap();
+/// assert_eq!(vbo.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Buffer<T, A: GpuVisibleAlloc = LinearAllocator>(Vec<T, A>, LiveCount);
+
+impl<T> Buffer<T> {
+    /// Create an empty buffer in LINEAR memory with room for `capacity` vertices.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if `T` is
+    /// zero-sized or too large to be used as a vertex (more than 255 bytes).
+    pub fn with_capacity(capacity: usize) -> crate::Result<Self> {
+        Self::with_capacity_in(capacity, LinearAllocator)
+    }
+}
+
+impl<T, A: GpuVisibleAlloc> Buffer<T, A> {
+    /// Create an empty buffer with room for `capacity` vertices, allocated
+    /// with `alloc`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if `T` is
+    /// zero-sized or too large to be used as a vertex (more than 255 bytes).
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> crate::Result<Self> {
+        Self::validate_layout()?;
+        Ok(Self(
+            Vec::with_capacity_in(capacity, alloc),
+            LiveCount::buffer(),
+        ))
+    }
+
+    /// Create a buffer from a vector already allocated in GPU-visible memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if `T` is
+    /// zero-sized or too large to be used as a vertex (more than 255 bytes).
+    pub fn from_vec(data: Vec<T, A>) -> crate::Result<Self> {
+        Self::validate_layout()?;
+        Ok(Self(data, LiveCount::buffer()))
+    }
+
+    /// Consume the buffer, returning the underlying vector.
+    pub fn into_inner(self) -> Vec<T, A> {
+        self.0
+    }
+
+    /// Append a vertex to the end of the buffer.
+    pub fn push(&mut self, vertex: T) {
+        self.0.push(vertex);
+    }
+
+    /// Remove all vertices from the buffer, keeping its allocation.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Flush the CPU data cache for the whole buffer, so that changes made
+    /// through [`DerefMut`] are visible to the GPU. [`update`](Self::update)
+    /// does this automatically for the range it writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::System`](crate::Error::System) if the cache could not
+    /// be flushed.
+    #[doc(alias = "GSPGPU_FlushDataCache")]
+    pub fn flush(&self) -> crate::Result<()> {
+        flush_data_cache(&self.0)
+    }
+
+    fn validate_layout() -> crate::Result<()> {
+        let size = std::mem::size_of::<T>();
+        // Allocators honor the alignment of `T`, so the only constraint
+        // left is that the GPU can step from one vertex to the next.
+        if size == 0 || size > MAX_STRIDE {
+            Err(crate::Error::InvalidSize)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T: Clone> Buffer<T> {
+    /// Create a buffer in LINEAR memory containing a copy of `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if `T` is
+    /// zero-sized or too large to be used as a vertex (more than 255 bytes).
+    pub fn from_slice(data: &[T]) -> crate::Result<Self> {
+        Self::from_slice_in(data, LinearAllocator)
+    }
+}
+
+impl<T: Clone, A: GpuVisibleAlloc> Buffer<T, A> {
+    /// Create a buffer containing a copy of `data`, allocated with `alloc`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if `T` is
+    /// zero-sized or too large to be used as a vertex (more than 255 bytes).
+    pub fn from_slice_in(data: &[T], alloc: A) -> crate::Result<Self> {
+        let mut buf = Self::with_capacity_in(data.len(), alloc)?;
+        buf.0.extend_from_slice(data);
+        Ok(buf)
+    }
+}
+
+impl<T: Copy, A: GpuVisibleAlloc> Buffer<T, A> {
+    /// Overwrite the vertices starting at `offset` with `data`, and flush them
+    /// from the CPU cache so the GPU sees the new contents.
+    ///
+    /// The buffer is never reallocated, so this can be used to stream new
+    /// geometry into the same allocation every frame. Since a [`Slice`]
+    /// borrows the buffer, it has to be registered again after updating it,
+    /// but that only records the buffer's address and doesn't copy any data.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`](crate::Error::InvalidSize) if `data` doesn't
+    ///   fit in the buffer at `offset`
+    /// * [`Error::System`](crate::Error::System) if the cache could not be
+    ///   flushed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::buffer::Buffer;
+    /// let mut vbo = Buffer::from_slice(&[0_u32; 4]).unwrap();
+    /// vbo.update(2, &[7, 8]).unwrap();
+    /// assert_eq!(&vbo[..], &[0, 0, 7, 8]);
+    ///
+    /// assert!(vbo.update(3, &[1, 2]).is_err());
+    /// ```
+    #[doc(alias = "GSPGPU_FlushDataCache")]
+    pub fn update(&mut self, offset: usize, data: &[T]) -> crate::Result<()> {
+        let dest = offset
+            .checked_add(data.len())
+            .and_then(|end| self.0.get_mut(offset..end))
+            .ok_or(crate::Error::InvalidSize)?;
+
+        dest.copy_from_slice(data);
+        flush_data_cache(dest)
+    }
+}
+
+/// Flush the CPU data cache for the given memory, so it can be read by the GPU.
+pub(crate) fn flush_data_cache<T>(data: &[T]) -> crate::Result<()> {
+    let size = std::mem::size_of_val(data).try_into()?;
+    let res = unsafe { ctru_sys::GSPGPU_FlushDataCache(data.as_ptr().cast(), size) };
+    if res < 0 {
+        Err(crate::Error::System(res))
+    } else {
+        Ok(())
+    }
+}
+
+impl<T, A: GpuVisibleAlloc> Deref for Buffer<T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, A: GpuVisibleAlloc> DerefMut for Buffer<T, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, A: GpuVisibleAlloc> Extend<T> for Buffer<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
 
 /// Vertex buffer info. This struct is used to describe the shape of the buffer
 /// data to be sent to the GPU for rendering.
@@ -64,6 +279,64 @@ pub enum Primitive {
     GeometryPrim = ctru_sys::GPU_GEOMETRY_PRIM,
 }
 
+/// A type which can be used for the elements of an index buffer, drawn with
+/// [`Instance::draw_elements`](crate::Instance::draw_elements). The GPU only
+/// supports [`u8`] and [`u16`] indices.
+pub trait IndexType: Copy + private::Sealed {
+    /// The `citro3d` index type constant for this type.
+    #[doc(hidden)]
+    const RAW: libc::c_int;
+}
+
+impl IndexType for u8 {
+    const RAW: libc::c_int = citro3d_sys::C3D_UNSIGNED_BYTE as libc::c_int;
+}
+
+impl IndexType for u16 {
+    const RAW: libc::c_int = citro3d_sys::C3D_UNSIGNED_SHORT as libc::c_int;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+}
+
+/// Vertex data being sent to the GPU in immediate mode, without a vertex
+/// buffer. See [`Instance::immediate`](crate::Instance::immediate).
+///
+/// Each vertex is made up of one call to [`attrib`](Self::attrib) per
+/// attribute in the current [`attrib::Info`], in register order.
+#[derive(Debug)]
+pub struct Immediate {
+    attrib_count: usize,
+}
+
+impl Immediate {
+    pub(crate) fn new() -> Self {
+        Self { attrib_count: 0 }
+    }
+
+    pub(crate) fn attrib_count(&self) -> usize {
+        self.attrib_count
+    }
+
+    /// Send the next vertex attribute.
+    #[doc(alias = "C3D_ImmSendAttrib")]
+    pub fn attrib(&mut self, x: f32, y: f32, z: f32, w: f32) {
+        unsafe { citro3d_sys::C3D_ImmSendAttrib(x, y, z, w) };
+        self.attrib_count += 1;
+    }
+
+    /// Send every attribute of a single vertex, in order.
+    pub fn vertex<const N: usize>(&mut self, attribs: [[f32; 4]; N]) {
+        for [x, y, z, w] in attribs {
+            self.attrib(x, y, z, w);
+        }
+    }
+}
+
 impl Default for Info {
     #[doc(alias = "BufInfo_Init")]
     fn default() -> Self {
@@ -140,4 +413,24 @@ impl Info {
             }),
         }
     }
+
+    /// Register an owned [`Buffer`]. This is the same as [`Info::add`], except
+    /// that the buffer is guaranteed to live in memory the GPU can read.
+    ///
+    /// # Errors
+    ///
+    /// Registering the buffer fails if the maximum number (12) of VBOs are
+    /// already registered.
+    #[doc(alias = "BufInfo_Add")]
+    pub fn add_buffer<'this, 'vbo, 'idx, T, A: GpuVisibleAlloc>(
+        &'this mut self,
+        buffer: &'vbo Buffer<T, A>,
+        attrib_info: &attrib::Info,
+    ) -> crate::Result<Slice<'idx>>
+    where
+        'this: 'idx,
+        'vbo: 'idx,
+    {
+        self.add(buffer, attrib_info)
+    }
 }
@@ -0,0 +1,312 @@
+//! Simple camera controllers, which turn user input from [`Hid`] into view
+//! matrices.
+//!
+//! Both controllers track a yaw (rotation around the Y axis) and a pitch
+//! (rotation up or down), with the pitch clamped to just short of straight up
+//! or down so the view never flips over.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use ctru::services::hid::{Hid, KeyPad};
+
+use crate::math::{CoordinateOrientation, FVec3, Matrix4};
+
+/// The maximum absolute value reported for each axis of the circle pad.
+const CIRCLE_PAD_MAX: f32 = 156.0;
+
+/// Circle pad readings smaller than this are ignored, since the pad rarely
+/// rests at exactly zero.
+const CIRCLE_PAD_DEADZONE: f32 = 15.0;
+
+/// The furthest the pitch can go from horizontal, in radians.
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+/// The closest an [`Orbit`] camera can get to its target. At zero distance the
+/// camera would have no direction to look in.
+const MIN_ORBIT_DISTANCE: f32 = 0.01;
+
+/// Orientation shared by the camera controllers.
+#[derive(Clone, Copy, Debug, Default)]
+struct Angles {
+    yaw: f32,
+    pitch: f32,
+}
+
+impl Angles {
+    fn rotate(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = (self.yaw + yaw).rem_euclid(TAU);
+        self.pitch = (self.pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// The unit vector pointing in the direction of these angles. A yaw and
+    /// pitch of zero point into the screen.
+    fn direction(self, coordinates: CoordinateOrientation) -> FVec3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let into_screen = match coordinates {
+            CoordinateOrientation::LeftHanded => 1.0,
+            CoordinateOrientation::RightHanded => -1.0,
+        };
+
+        FVec3::new(
+            cos_pitch * sin_yaw,
+            sin_pitch,
+            into_screen * cos_pitch * cos_yaw,
+        )
+    }
+}
+
+/// Read the circle pad as a pair of values from -1.0 to 1.0, with the deadzone
+/// removed.
+fn circle_pad(hid: &Hid) -> (f32, f32) {
+    let (x, y) = hid.circlepad_position();
+    let axis = |v: i16| {
+        let v = f32::from(v);
+        if v.abs() < CIRCLE_PAD_DEADZONE {
+            0.0
+        } else {
+            (v / CIRCLE_PAD_MAX).clamp(-1.0, 1.0)
+        }
+    };
+    (axis(x), axis(y))
+}
+
+/// Tracks the touch screen between frames to measure drag distance.
+#[derive(Clone, Copy, Debug, Default)]
+struct TouchDrag {
+    last: Option<(u16, u16)>,
+}
+
+impl TouchDrag {
+    /// Get the distance the stylus moved since the last frame, in pixels.
+    fn update(&mut self, hid: &Hid) -> (f32, f32) {
+        if !hid.keys_held().contains(KeyPad::TOUCH) {
+            self.last = None;
+            return (0.0, 0.0);
+        }
+
+        let (x, y) = hid.touch_position();
+        let delta = match self.last {
+            Some((last_x, last_y)) => (
+                f32::from(x) - f32::from(last_x),
+                f32::from(y) - f32::from(last_y),
+            ),
+            None => (0.0, 0.0),
+        };
+        self.last = Some((x, y));
+        delta
+    }
+}
+
+/// A first-person camera, which looks around from a fixed position using the
+/// circle pad.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::camera;
+/// # use citro3d::math::FVec3;
+/// # use ctru::services::hid::Hid;
+/// #
+/// # let mut hid = Hid::new().unwrap();
+/// let mut camera = camera::FirstPerson::new(FVec3::new(0.0, 1.0, 0.0));
+///
+/// // Once per frame:
+/// hid.scan_input();
+/// camera.update(&hid);
+/// let view = camera.view_matrix();
+/// ```
+#[derive(Clone, Debug)]
+pub struct FirstPerson {
+    /// The position of the camera.
+    pub position: FVec3,
+    /// How quickly the camera turns with the circle pad held all the way, in
+    /// radians per update.
+    pub look_speed: f32,
+    coordinates: CoordinateOrientation,
+    angles: Angles,
+}
+
+impl FirstPerson {
+    /// Create a camera at the given position, looking into the screen.
+    pub fn new(position: FVec3) -> Self {
+        Self {
+            position,
+            look_speed: 0.05,
+            coordinates: CoordinateOrientation::default(),
+            angles: Angles::default(),
+        }
+    }
+
+    /// Set the coordinate system's orientation used to build the view matrix.
+    /// This should match the one used for the projection.
+    pub fn coordinates(mut self, orientation: CoordinateOrientation) -> Self {
+        self.coordinates = orientation;
+        self
+    }
+
+    /// Turn the camera according to the current circle pad input.
+    pub fn update(&mut self, hid: &Hid) {
+        let (x, y) = circle_pad(hid);
+        self.rotate(x * self.look_speed, y * self.look_speed);
+    }
+
+    /// Turn the camera by the given angles, in radians. Positive values turn
+    /// right and up, respectively.
+    pub fn rotate(&mut self, yaw: f32, pitch: f32) {
+        self.angles.rotate(yaw, pitch);
+    }
+
+    /// The unit vector in the direction the camera is looking.
+    pub fn forward(&self) -> FVec3 {
+        self.angles.direction(self.coordinates)
+    }
+
+    /// Build the view matrix for the camera's current position and orientation.
+    #[doc(alias = "Mtx_LookAt")]
+    pub fn view_matrix(&self) -> Matrix4 {
+        Matrix4::looking_at(
+            self.position,
+            self.position + self.forward(),
+            FVec3::new(0.0, 1.0, 0.0),
+            self.coordinates,
+        )
+    }
+}
+
+/// A camera which orbits around a target point, controlled by the circle pad
+/// or by dragging on the touch screen.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::camera;
+/// # use citro3d::math::FVec3;
+/// # use ctru::services::hid::Hid;
+/// #
+/// # let mut hid = Hid::new().unwrap();
+/// let mut camera = camera::Orbit::new(FVec3::splat(0.0), 5.0);
+///
+/// // Once per frame:
+/// hid.scan_input();
+/// camera.update(&hid);
+/// let view = camera.view_matrix();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Orbit {
+    /// The point the camera orbits around and looks at.
+    pub target: FVec3,
+    /// How quickly the camera orbits with the circle pad held all the way, in
+    /// radians per update.
+    pub look_speed: f32,
+    /// How far the camera orbits when dragging on the touch screen, in radians
+    /// per pixel.
+    pub touch_speed: f32,
+    distance: f32,
+    coordinates: CoordinateOrientation,
+    angles: Angles,
+    touch: TouchDrag,
+}
+
+impl Orbit {
+    /// Create a camera orbiting `target` at the given `distance`, looking into
+    /// the screen. The distance is clamped to at least `0.01`.
+    pub fn new(target: FVec3, distance: f32) -> Self {
+        Self {
+            target,
+            look_speed: 0.05,
+            touch_speed: 0.01,
+            distance: distance.max(MIN_ORBIT_DISTANCE),
+            coordinates: CoordinateOrientation::default(),
+            angles: Angles::default(),
+            touch: TouchDrag::default(),
+        }
+    }
+
+    /// Set the coordinate system's orientation used to build the view matrix.
+    /// This should match the one used for the projection.
+    pub fn coordinates(mut self, orientation: CoordinateOrientation) -> Self {
+        self.coordinates = orientation;
+        self
+    }
+
+    /// Orbit the camera according to the current circle pad and touch input.
+    pub fn update(&mut self, hid: &Hid) {
+        let (x, y) = circle_pad(hid);
+        self.rotate(x * self.look_speed, y * self.look_speed);
+
+        // Dragging pulls the scene along with the stylus, so the camera moves
+        // the opposite way. Touch Y coordinates increase downwards, which
+        // already has the opposite sign to the camera's pitch.
+        let (dx, dy) = self.touch.update(hid);
+        self.rotate(-dx * self.touch_speed, dy * self.touch_speed);
+    }
+
+    /// Orbit the camera by the given angles, in radians. Positive values move
+    /// the camera right and up around the target, respectively.
+    pub fn rotate(&mut self, yaw: f32, pitch: f32) {
+        self.angles.rotate(yaw, pitch);
+    }
+
+    /// The distance from the camera to its target.
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    /// Move the camera towards (negative) or away from (positive) its target.
+    /// The camera stays at least `0.01` away from the target.
+    pub fn zoom(&mut self, amount: f32) {
+        self.distance = (self.distance + amount).max(MIN_ORBIT_DISTANCE);
+    }
+
+    /// The camera's position.
+    pub fn position(&self) -> FVec3 {
+        // Moving the camera right and up around the target means it looks
+        // left and down towards it
+        let facing = Angles {
+            yaw: -self.angles.yaw,
+            pitch: -self.angles.pitch,
+        };
+        self.target - facing.direction(self.coordinates) * self.distance
+    }
+
+    /// Build the view matrix for the camera's current position and orientation.
+    #[doc(alias = "Mtx_LookAt")]
+    pub fn view_matrix(&self) -> Matrix4 {
+        Matrix4::looking_at(
+            self.position(),
+            self.target,
+            FVec3::new(0.0, 1.0, 0.0),
+            self.coordinates,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pitch_is_clamped() {
+        let mut angles = Angles::default();
+        angles.rotate(0.0, 10.0);
+        assert_eq!(angles.pitch, MAX_PITCH);
+
+        angles.rotate(TAU + 1.0, -20.0);
+        assert_eq!(angles.pitch, -MAX_PITCH);
+        assert!((angles.yaw - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn orbit_stays_off_target() {
+        let mut camera = Orbit::new(FVec3::splat(0.0), 0.0);
+        assert_eq!(camera.distance(), MIN_ORBIT_DISTANCE);
+
+        camera.zoom(5.0);
+        camera.zoom(-10.0);
+        assert_eq!(camera.distance(), MIN_ORBIT_DISTANCE);
+        assert_ne!(camera.position(), camera.target);
+    }
+}
@@ -0,0 +1,191 @@
+//! A typed RGBA color, to avoid guessing the channel order of raw `u32` colors.
+//!
+//! `citro3d` isn't consistent about how colors are packed into integers: render
+//! target clears take `0xRRGGBBAA`, while texture combiner colors are stored
+//! with red in the *least* significant byte. APIs taking a [`Color`] handle the
+//! conversion themselves.
+
+/// A color with 8-bit red, green, blue, and alpha channels.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::color::Color;
+/// let sky = Color::rgb(0x68, 0xB0, 0xD8);
+/// assert_eq!(sky.to_rgba32(), 0x68_B0_D8_FF);
+/// assert_eq!(Color::from_rgba32(0x68_B0_D8_FF), sky);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// The red channel.
+    pub r: u8,
+    /// The green channel.
+    pub g: u8,
+    /// The blue channel.
+    pub b: u8,
+    /// The alpha channel, where `0` is fully transparent.
+    pub a: u8,
+}
+
+impl Color {
+    /// Opaque black.
+    pub const BLACK: Self = Self::rgb(0, 0, 0);
+    /// Opaque white.
+    pub const WHITE: Self = Self::rgb(0xFF, 0xFF, 0xFF);
+    /// Fully transparent black.
+    pub const TRANSPARENT: Self = Self::new(0, 0, 0, 0);
+
+    /// Create a color from its red, green, blue, and alpha channels.
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Create an opaque color from its red, green, and blue channels.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::new(r, g, b, 0xFF)
+    }
+
+    /// Create a color from channels in the range `0.0..=1.0`. Values outside
+    /// that range are clamped.
+    pub fn from_f32(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self::new(channel(r), channel(g), channel(b), channel(a))
+    }
+
+    /// Create an opaque color from hue (in degrees), saturation, and value
+    /// (both from `0.0` to `1.0`). The hue wraps around, and saturation and
+    /// value are clamped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::color::Color;
+    /// assert_eq!(Color::from_hsv(120.0, 1.0, 1.0), Color::rgb(0, 0xFF, 0));
+    /// ```
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let min = value - chroma;
+
+        let (r, g, b) = match sector as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        Self::from_f32(r + min, g + min, b + min, 1.0)
+    }
+
+    /// Approximate the opaque color of a black body at the given temperature
+    /// in Kelvin, e.g. about 1900K for candlelight, 2700K for incandescent
+    /// bulbs, or 6500K for daylight. Temperatures are clamped to the range
+    /// 1000K to 40000K.
+    pub fn from_kelvin(temperature: f32) -> Self {
+        // Curve fit from https://tannerhelland.com/2012/09/18/convert-temperature-rgb-algorithm-code.html
+        let t = temperature.clamp(1000.0, 40000.0) / 100.0;
+
+        let r = if t <= 66.0 {
+            255.0
+        } else {
+            329.698_73 * (t - 60.0).powf(-0.133_204_76)
+        };
+        let g = if t <= 66.0 {
+            99.470_8 * t.ln() - 161.119_57
+        } else {
+            288.122_17 * (t - 60.0).powf(-0.075_514_85)
+        };
+        let b = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.517_73 * (t - 10.0).ln() - 305.044_8
+        };
+
+        Self::from_f32(r / 255.0, g / 255.0, b / 255.0, 1.0)
+    }
+
+    /// Unpack a color stored as `0xRRGGBBAA`, e.g. as used by
+    /// [`Target::clear`](crate::render::Target::clear).
+    pub const fn from_rgba32(rgba: u32) -> Self {
+        let [r, g, b, a] = rgba.to_be_bytes();
+        Self::new(r, g, b, a)
+    }
+
+    /// Pack the color as `0xRRGGBBAA`, e.g. for
+    /// [`Target::clear`](crate::render::Target::clear).
+    pub const fn to_rgba32(self) -> u32 {
+        u32::from_be_bytes([self.r, self.g, self.b, self.a])
+    }
+
+    /// Pack the color the way the GPU's color registers expect it, with red in
+    /// the least significant byte (`0xAABBGGRR`).
+    pub(crate) const fn to_gpu(self) -> u32 {
+        u32::from_le_bytes([self.r, self.g, self.b, self.a])
+    }
+}
+
+impl From<[u8; 4]> for Color {
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Self::new(r, g, b, a)
+    }
+}
+
+impl From<Color> for [u8; 4] {
+    fn from(color: Color) -> Self {
+        [color.r, color.g, color.b, color.a]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packing_orders() {
+        let color = Color::new(0x12, 0x34, 0x56, 0x78);
+        assert_eq!(color.to_rgba32(), 0x1234_5678);
+        assert_eq!(color.to_gpu(), 0x7856_3412);
+        assert_eq!(Color::from_rgba32(0x1234_5678), color);
+    }
+
+    #[test]
+    fn hsv_sectors() {
+        assert_eq!(Color::from_hsv(0.0, 1.0, 1.0), Color::rgb(0xFF, 0, 0));
+        assert_eq!(Color::from_hsv(60.0, 1.0, 1.0), Color::rgb(0xFF, 0xFF, 0));
+        assert_eq!(Color::from_hsv(-120.0, 1.0, 1.0), Color::rgb(0, 0, 0xFF));
+        assert_eq!(
+            Color::from_hsv(300.0, 0.0, 0.5),
+            Color::rgb(0x80, 0x80, 0x80)
+        );
+    }
+
+    #[test]
+    fn kelvin_warm_to_cool() {
+        let candle = Color::from_kelvin(1900.0);
+        let daylight = Color::from_kelvin(6600.0);
+        let sky = Color::from_kelvin(15000.0);
+
+        assert_eq!(candle.r, 0xFF);
+        assert!(candle.b < candle.g && candle.g < candle.r);
+        assert!(daylight.r > 0xF0 && daylight.g > 0xF0 && daylight.b == 0xFF);
+        assert!(sky.r < sky.b);
+    }
+
+    #[test]
+    fn from_f32_clamps() {
+        assert_eq!(
+            Color::from_f32(2.0, 0.5, -1.0, 1.0),
+            Color::rgb(0xFF, 0x80, 0)
+        );
+    }
+}
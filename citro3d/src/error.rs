@@ -36,6 +36,8 @@ pub enum Error {
     InvalidName,
     /// The requested resource could not be found.
     NotFound,
+    /// There was not enough memory available for the requested allocation.
+    OutOfMemory,
 }
 
 impl From<TryFromIntError> for Error {
@@ -0,0 +1,311 @@
+//! Runtime compression of RGBA images into the ETC1 and ETC1A4 texture
+//! formats, which take up an eighth and a quarter of the space of RGBA8.
+//!
+//! The encoder favours speed over quality: each 4x4 block is encoded using
+//! ETC1's "individual" mode only, picking the best orientation and modifier
+//! tables by exhaustive search. This is usually good enough for downloaded or
+//! procedurally generated images, but offline tools like `tex3ds` will produce
+//! better results for assets known ahead of time.
+//!
+//! Like the other texture formats, the output is stored in 8x8 tiles starting
+//! from the bottom of the image, with the four 4x4 blocks of each tile in
+//! Z-order. See <https://www.3dbrew.org/wiki/GPU/Textures> for details.
+
+/// The width and height of a tile, in pixels.
+const TILE_SIZE: usize = 8;
+
+/// The intensity modifier tables defined by the ETC1 format.
+const MODIFIERS: [[i32; 2]; 8] = [
+    [2, 8],
+    [5, 17],
+    [9, 29],
+    [13, 42],
+    [18, 60],
+    [24, 80],
+    [33, 106],
+    [47, 183],
+];
+
+/// Compress a linear, top-to-bottom image with 8-bit RGBA pixels into ETC1.
+/// The alpha channel is discarded.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if the image is
+/// empty, `width` or `height` is not a multiple of 8, or `rgba` is not exactly
+/// `width * height * 4` bytes long.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::etc1;
+/// let pixels = [0x40, 0x80, 0xc0, 0xff].repeat(16 * 8);
+/// let encoded = etc1::encode_etc1(&pixels, 16, 8).unwrap();
+/// assert_eq!(encoded.len(), 16 * 8 / 2);
+/// ```
+pub fn encode_etc1(rgba: &[u8], width: usize, height: usize) -> crate::Result<Vec<u8>> {
+    encode(rgba, width, height, false)
+}
+
+/// Compress a linear, top-to-bottom image with 8-bit RGBA pixels into ETC1A4,
+/// which stores a 4-bit alpha value for each pixel alongside the ETC1 data.
+///
+/// # Errors
+///
+/// Fails under the same conditions as [`encode_etc1`].
+pub fn encode_etc1a4(rgba: &[u8], width: usize, height: usize) -> crate::Result<Vec<u8>> {
+    encode(rgba, width, height, true)
+}
+
+fn encode(rgba: &[u8], width: usize, height: usize, alpha: bool) -> crate::Result<Vec<u8>> {
+    let size = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(4))
+        .ok_or(crate::Error::InvalidSize)?;
+
+    if size == 0 || width % TILE_SIZE != 0 || height % TILE_SIZE != 0 || rgba.len() != size {
+        return Err(crate::Error::InvalidSize);
+    }
+
+    // Get a pixel with the Y axis flipped, so (0, 0) is the bottom-left
+    let pixel = |x: usize, y: usize| {
+        let offset = ((height - 1 - y) * width + x) * 4;
+        [
+            rgba[offset],
+            rgba[offset + 1],
+            rgba[offset + 2],
+            rgba[offset + 3],
+        ]
+    };
+
+    let block_bytes = if alpha { 16 } else { 8 };
+    let mut out = Vec::with_capacity(width * height / 16 * block_bytes);
+
+    for tile_y in (0..height).step_by(TILE_SIZE) {
+        for tile_x in (0..width).step_by(TILE_SIZE) {
+            for (block_x, block_y) in [(0, 0), (4, 0), (0, 4), (4, 4)] {
+                let block: [[u8; 4]; 16] = std::array::from_fn(|i| {
+                    // Pixels are indexed column by column within a block
+                    pixel(tile_x + block_x + i / 4, tile_y + block_y + i % 4)
+                });
+
+                if alpha {
+                    out.extend_from_slice(&encode_alpha(&block).to_le_bytes());
+                }
+                out.extend_from_slice(&encode_block(&block).to_le_bytes());
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Pack the alpha of each pixel in a block into 4 bits, in pixel index order.
+fn encode_alpha(block: &[[u8; 4]; 16]) -> u64 {
+    block.iter().enumerate().fold(0, |bits, (i, [.., a])| {
+        bits | (u64::from(a >> 4) << (4 * i))
+    })
+}
+
+/// Encode the colors of a block, with pixels in column-major order.
+fn encode_block(block: &[[u8; 4]; 16]) -> u64 {
+    // Subblock membership for each pixel index, for each orientation: either
+    // two 2x4 halves side by side, or two 4x2 halves on top of each other.
+    let side_by_side = |i: usize| i / 8;
+    let stacked = |i: usize| (i % 4) / 2;
+
+    [(false, side_by_side as fn(usize) -> usize), (true, stacked)]
+        .into_iter()
+        .map(|(flip, half)| {
+            let mut word = u64::from(flip) << 32;
+            let mut error = 0;
+
+            for sub in 0..2 {
+                let pixels = (0..16).filter(|&i| half(i) == sub);
+                let base = average_444(pixels.clone().map(|i| block[i]));
+                let (table, indices, sub_error) = best_table(base, pixels, block);
+
+                // Subblock 0 uses the high nibble of each color byte and
+                // codeword 1, subblock 1 the low nibble and codeword 2
+                let shift = 4 * sub;
+                word |= u64::from(base[0]) << (60 - shift)
+                    | u64::from(base[1]) << (52 - shift)
+                    | u64::from(base[2]) << (44 - shift);
+                word |= (table as u64) << (37 - 3 * sub);
+                word |= indices;
+                error += sub_error;
+            }
+
+            (error, word)
+        })
+        .min_by_key(|&(error, _)| error)
+        .map(|(_, word)| word)
+        .unwrap()
+}
+
+/// Average the RGB values of some pixels, quantized to 4 bits per channel.
+fn average_444(pixels: impl Iterator<Item = [u8; 4]>) -> [u8; 3] {
+    let mut sum = [0_u32; 3];
+    let mut count = 0;
+    for [r, g, b, _] in pixels {
+        sum[0] += u32::from(r);
+        sum[1] += u32::from(g);
+        sum[2] += u32::from(b);
+        count += 1;
+    }
+
+    sum.map(|channel| {
+        // Round to the nearest value representable by replicating 4 bits
+        let average = (channel + count / 2) / count;
+        ((average * 15 + 127) / 255) as u8
+    })
+}
+
+/// Find the modifier table which best represents some pixels relative to a
+/// base color, returning the table index, the pixel index bits (already
+/// positioned in the block), and the total squared error.
+fn best_table(
+    base: [u8; 3],
+    pixels: impl Iterator<Item = usize> + Clone,
+    block: &[[u8; 4]; 16],
+) -> (usize, u64, u32) {
+    let base = base.map(|c| i32::from(c << 4 | c));
+
+    (0..MODIFIERS.len())
+        .map(|table| {
+            let mut indices = 0;
+            let mut error = 0;
+
+            for i in pixels.clone() {
+                let [r, g, b, _] = block[i].map(i32::from);
+                let (index, pixel_error) = modifier_values(table)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, modifier)| {
+                        let diff = |channel: i32, target: i32| {
+                            let value = (channel + modifier).clamp(0, 255);
+                            (value - target).pow(2) as u32
+                        };
+                        let error = diff(base[0], r) + diff(base[1], g) + diff(base[2], b);
+                        (index as u64, error)
+                    })
+                    .min_by_key(|&(_, error)| error)
+                    .unwrap();
+
+                indices |= (index >> 1) << (16 + i) | (index & 1) << i;
+                error += pixel_error;
+            }
+
+            (table, indices, error)
+        })
+        .min_by_key(|&(.., error)| error)
+        .unwrap()
+}
+
+/// The modifier for each 2-bit pixel index value in the given table.
+fn modifier_values(table: usize) -> [i32; 4] {
+    let [small, large] = MODIFIERS[table];
+    [small, large, -small, -large]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode a block in individual mode, returning RGB in pixel index order.
+    /// Bit positions follow the ETC1 specification rather than the encoder.
+    fn decode_block(word: u64) -> [[u8; 3]; 16] {
+        let nibble = |bit: u32| {
+            let c = (word >> bit & 0xf) as i32;
+            c << 4 | c
+        };
+        // R1 G1 B1 and codeword 1, then R2 G2 B2 and codeword 2
+        let bases = [
+            [nibble(60), nibble(52), nibble(44)],
+            [nibble(56), nibble(48), nibble(40)],
+        ];
+        let tables = [(word >> 37 & 0x7) as usize, (word >> 34 & 0x7) as usize];
+        let flip = word >> 32 & 1 == 1;
+
+        std::array::from_fn(|i| {
+            // Pixel index i is column x = i / 4, row y = i % 4
+            let (x, y) = (i / 4, i % 4);
+            let sub = if flip {
+                usize::from(y >= 2)
+            } else {
+                usize::from(x >= 2)
+            };
+            let msb = word >> (16 + i) & 1;
+            let lsb = word >> i & 1;
+            let modifier = modifier_values(tables[sub])[(msb << 1 | lsb) as usize];
+            bases[sub].map(|c| (c + modifier).clamp(0, 255) as u8)
+        })
+    }
+
+    #[test]
+    fn gradient_block_roundtrips() {
+        let block: [[u8; 4]; 16] = std::array::from_fn(|i| {
+            let v = (i * 16) as u8;
+            [v, v, v, 255]
+        });
+
+        let decoded = decode_block(encode_block(&block));
+        for (original, decoded) in block.iter().zip(decoded) {
+            for (a, b) in original.iter().zip(decoded) {
+                assert!(a.abs_diff(b) <= 24, "{original:?} vs {decoded:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn solid_block_is_close() {
+        // Every pixel has a modifier applied, so the smallest possible error
+        // for a color exactly representable in 4 bits is 2 per channel
+        let block = [[0x33, 0x66, 0x99, 0xff]; 16];
+        let decoded = decode_block(encode_block(&block));
+        assert!(decoded
+            .iter()
+            .all(|&rgb| rgb == [0x35, 0x68, 0x9b] || rgb == [0x31, 0x64, 0x97]));
+    }
+
+    #[test]
+    fn halves_use_their_own_tables() {
+        // A flat dark left half needs the smallest table, while the textured
+        // bright right half needs a large one
+        let block: [[u8; 4]; 16] = std::array::from_fn(|i| {
+            if i < 8 {
+                [40, 40, 40, 255]
+            } else if i % 2 == 0 {
+                [250, 250, 250, 255]
+            } else {
+                [120, 120, 120, 255]
+            }
+        });
+
+        let word = encode_block(&block);
+        assert_eq!(word >> 32 & 1, 0, "expected side-by-side subblocks");
+        assert_ne!(word >> 37 & 0x7, word >> 34 & 0x7);
+
+        let decoded = decode_block(word);
+        for (original, decoded) in block.iter().zip(decoded) {
+            for (a, b) in original.iter().zip(decoded) {
+                assert!(a.abs_diff(b) <= 16, "{original:?} vs {decoded:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn alpha_is_packed_per_pixel() {
+        let block: [[u8; 4]; 16] = std::array::from_fn(|i| [0, 0, 0, (i * 17) as u8]);
+        assert_eq!(encode_alpha(&block), 0xfedc_ba98_7654_3210);
+    }
+
+    #[test]
+    fn output_sizes() {
+        let pixels = vec![0; 8 * 16 * 4];
+        assert_eq!(encode_etc1(&pixels, 8, 16).unwrap().len(), 64);
+        assert_eq!(encode_etc1a4(&pixels, 8, 16).unwrap().len(), 128);
+        assert!(encode_etc1(&pixels, 4, 32).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Fog support. The PICA200 blends each fragment towards a fog color by a
+//! factor looked up from its depth, so fog is described by a [`Lut`] mapping
+//! depth to visibility.
+//!
+//! A [`Fog`] is enabled with [`Instance::set_fog`](crate::Instance::set_fog).
+//!
+//! See also [`fog.h` documentation](https://oreo639.github.io/citro3d/fog_8h.html).
+
+use std::fmt;
+use std::mem::MaybeUninit;
+
+use crate::color::Color;
+
+/// A lookup table from fragment depth to fog visibility, where `1.0` leaves
+/// the fragment unchanged and `0.0` replaces it with the fog color.
+#[doc(alias = "C3D_FogLut")]
+#[derive(Clone)]
+pub struct Lut(pub(crate) citro3d_sys::C3D_FogLut);
+
+impl fmt::Debug for Lut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lut").finish_non_exhaustive()
+    }
+}
+
+impl Lut {
+    /// Create a lookup table by sampling `f` at 129 evenly spaced depth
+    /// values from `0.0` to `1.0` (inclusive).
+    #[doc(alias = "FogLut_FromArray")]
+    pub fn from_fn(mut f: impl FnMut(f32) -> f32) -> Self {
+        let samples: [f32; 129] = std::array::from_fn(|i| f(i as f32 / 128.0));
+
+        // The first 128 entries are the values, the last 128 the difference
+        // from each value to the next one.
+        let data: [f32; 256] = std::array::from_fn(|i| {
+            if i < 128 {
+                samples[i]
+            } else {
+                samples[i - 127] - samples[i - 128]
+            }
+        });
+
+        let mut lut = MaybeUninit::uninit();
+        // SAFETY: FogLut_FromArray reads exactly 256 values (128 values
+        // followed by 128 deltas) and writes every entry of the table
+        unsafe {
+            citro3d_sys::FogLut_FromArray(lut.as_mut_ptr(), data.as_ptr());
+            Self(lut.assume_init())
+        }
+    }
+
+    /// Create a lookup table from a function of the distance from the camera,
+    /// for a projection with the given `near` and `far` clip planes.
+    pub fn from_distance_fn(near: f32, far: f32, mut f: impl FnMut(f32) -> f32) -> Self {
+        Self::from_fn(|depth| f(depth_to_distance(depth, near, far)))
+    }
+
+    /// Fog which starts at distance `start` from the camera and completely
+    /// hides everything beyond `end`.
+    pub fn linear(start: f32, end: f32, near: f32, far: f32) -> Self {
+        Self::from_distance_fn(near, far, |distance| {
+            ((end - distance) / (end - start)).clamp(0.0, 1.0)
+        })
+    }
+
+    /// Exponential fog, with visibility `e^-(density * distance)`.
+    #[doc(alias = "FogLut_Exp")]
+    pub fn exp(density: f32, near: f32, far: f32) -> Self {
+        Self::exp_gradient(density, 1.0, near, far)
+    }
+
+    /// Squared exponential fog, with visibility `e^-(density * distance)²`.
+    /// Compared to [`Lut::exp`], this stays clearer close to the camera and
+    /// thickens more quickly further away.
+    #[doc(alias = "FogLut_Exp")]
+    pub fn exp2(density: f32, near: f32, far: f32) -> Self {
+        Self::exp_gradient(density, 2.0, near, far)
+    }
+
+    fn exp_gradient(density: f32, gradient: f32, near: f32, far: f32) -> Self {
+        let mut lut = MaybeUninit::uninit();
+        // SAFETY: FogLut_Exp writes every entry of the table
+        unsafe {
+            citro3d_sys::FogLut_Exp(lut.as_mut_ptr(), density, gradient, near, far);
+            Self(lut.assume_init())
+        }
+    }
+}
+
+/// Convert a depth value (as used to index the fog LUT) back to the distance
+/// from the camera, matching `FogLut_CalcZ`.
+fn depth_to_distance(depth: f32, near: f32, far: f32) -> f32 {
+    far * near / (depth * (far - near) + near)
+}
+
+/// The fog configuration: a [`Lut`] and the color fragments fade towards.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::color::Color;
+/// # use citro3d::fog::{Fog, Lut};
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// let fog = Fog::new(Lut::exp2(0.05, 0.1, 100.0), Color::rgb(0xA0, 0xB0, 0xC0));
+/// instance.set_fog(Some(&fog));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Fog {
+    pub(crate) lut: Lut,
+    pub(crate) color: Color,
+    pub(crate) flip_depth: bool,
+}
+
+impl Fog {
+    /// Create a fog configuration fading towards `color` (whose alpha is
+    /// ignored) according to `lut`.
+    pub fn new(lut: Lut, color: Color) -> Self {
+        Self {
+            lut,
+            color,
+            flip_depth: false,
+        }
+    }
+
+    /// Invert the depth used to index the LUT, e.g. when not using the default
+    /// reversed depth range.
+    pub fn flip_depth(&mut self, flip: bool) -> &mut Self {
+        self.flip_depth = flip;
+        self
+    }
+
+    /// Set the color fragments fade towards. Its alpha is ignored.
+    #[doc(alias = "C3D_FogColor")]
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    /// Replace the lookup table.
+    pub fn lut(&mut self, lut: Lut) -> &mut Self {
+        self.lut = lut;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_maps_far_to_near() {
+        assert_eq!(depth_to_distance(0.0, 0.1, 100.0), 100.0);
+        assert!((depth_to_distance(1.0, 0.1, 100.0) - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn from_fn_matches_exp() {
+        let (density, near, far) = (0.05, 0.1, 100.0);
+
+        let expected = Lut::exp2(density, near, far);
+        let actual = Lut::from_distance_fn(near, far, |distance| {
+            (-(density * distance).powf(2.0)).exp()
+        });
+
+        // Each entry packs an 11-bit value and a 13-bit signed delta; allow
+        // for rounding differences between libm implementations.
+        for (&e, &a) in expected.0.data.iter().zip(actual.0.data.iter()) {
+            let value = |x: u32| (x >> 13) as i32;
+            let delta = |x: u32| ((x << 19) as i32) >> 19;
+            assert!((value(e) - value(a)).abs() <= 1, "{e:#x} != {a:#x}");
+            assert!((delta(e) - delta(a)).abs() <= 1, "{e:#x} != {a:#x}");
+        }
+    }
+}
@@ -1,3 +1,4 @@
+#![feature(allocator_api)]
 #![feature(custom_test_frameworks)]
 #![test_runner(test_runner::run_gdb)]
 #![feature(doc_cfg)]
@@ -16,27 +17,41 @@
 //! ## Feature flags
 #![doc = document_features::document_features!()]
 
+pub mod arena;
 pub mod attrib;
 pub mod buffer;
+pub mod camera;
+pub mod color;
 pub mod error;
+pub mod etc1;
+pub mod fog;
 pub mod math;
+pub mod memory;
+pub mod mesh;
+pub mod mipmap;
+pub mod prelude;
+pub mod proctex;
 pub mod render;
 pub mod shader;
+pub mod stats;
+pub mod swizzle;
+pub mod tex3ds;
 pub mod texenv;
 pub mod uniform;
 
 use std::cell::{OnceCell, RefMut};
 use std::fmt;
+use std::ops::Range;
 use std::rc::Rc;
 
-use ctru::services::gfx::Screen;
+use ctru::services::gfx::{Gfx, RawFrameBuffer, Screen, TopScreen3D};
 pub use error::{Error, Result};
 
 use self::texenv::TexEnv;
 use self::uniform::Uniform;
 
 pub mod macros {
-    //! Helper macros for working with shaders.
+    //! Helper macros for working with shaders and textures.
     pub use citro3d_macros::*;
 }
 
@@ -46,7 +61,18 @@ pub mod macros {
 #[must_use]
 pub struct Instance {
     texenvs: [OnceCell<TexEnv>; texenv::TEXENV_COUNT],
+    // The texture combiners last sent to the GPU
+    applied_texenvs: [Option<TexEnv>; texenv::TEXENV_COUNT],
     queue: Rc<RenderQueue>,
+    counters: stats::Counters,
+    last_frame: stats::Counters,
+    frame: u64,
+    // citro3d keeps pointers to the bound procedural texture and its LUTs
+    proctex: Option<Box<proctex::ProcTex>>,
+    fog: Option<Box<fog::Fog>>,
+    scissor: bool,
+    stencil: bool,
+    blend: render::Blend,
 }
 
 /// Representation of `citro3d`'s internal render queue. This is something that
@@ -89,7 +115,16 @@ impl Instance {
                     OnceCell::new(),
                     OnceCell::new(),
                 ],
+                applied_texenvs: Default::default(),
                 queue: Rc::new(RenderQueue),
+                counters: stats::Counters::default(),
+                last_frame: stats::Counters::default(),
+                frame: 0,
+                proctex: None,
+                fog: None,
+                scissor: false,
+                stencil: false,
+                blend: render::Blend::default(),
             })
         } else {
             Err(Error::FailedToInitialize)
@@ -99,9 +134,17 @@ impl Instance {
     /// Create a new render target with the specified size, color format,
     /// and depth format.
     ///
+    /// Since the screens are rotated, `width` and `height` are measured along
+    /// the framebuffer rather than the screen as seen by the user; see
+    /// [`render::TOP_SCREEN_SIZE`] and [`render::BOTTOM_SCREEN_SIZE`].
+    ///
     /// # Errors
     ///
-    /// Fails if the target could not be created with the given parameters.
+    /// * [`Error::InvalidSize`] if the target is empty or larger than the
+    ///   screen's framebuffer in either dimension (e.g. `400, 240` instead of
+    ///   `240, 400` for the top screen)
+    /// * [`Error::FailedToInitialize`] if the target could not be created with
+    ///   the given parameters
     #[doc(alias = "C3D_RenderTargetCreate")]
     #[doc(alias = "C3D_RenderTargetSetOutput")]
     pub fn render_target<'screen>(
@@ -114,18 +157,71 @@ impl Instance {
         render::Target::new(width, height, screen, depth_format, Rc::clone(&self.queue))
     }
 
+    /// Create render targets for all three outputs of the 3DS screens: the left
+    /// and right sides of the top screen, plus the bottom screen. Each target
+    /// is sized to match its screen's framebuffer.
+    ///
+    /// This borrows both screens of `gfx` mutably for as long as the targets live.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any of the targets could not be created with the given parameters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::Gfx;
+    /// #
+    /// let gfx = Gfx::new().unwrap();
+    /// let instance = citro3d::Instance::new().unwrap();
+    ///
+    /// let citro3d::render::ScreenTargets {
+    ///     top_left,
+    ///     top_right,
+    ///     bottom,
+    /// } = instance.screen_targets(&gfx, None).unwrap();
+    /// ```
+    pub fn screen_targets<'screen>(
+        &self,
+        gfx: &'screen Gfx,
+        depth_format: Option<render::DepthFormat>,
+    ) -> Result<render::ScreenTargets<'screen>> {
+        let (top_left, top_right) = TopScreen3D::from(&gfx.top_screen).split_mut();
+        let bottom = gfx.bottom_screen.borrow_mut();
+
+        let RawFrameBuffer { width, height, .. } = top_left.raw_framebuffer();
+        let top_left = self.render_target(width, height, top_left, depth_format)?;
+
+        let RawFrameBuffer { width, height, .. } = top_right.raw_framebuffer();
+        let top_right = self.render_target(width, height, top_right, depth_format)?;
+
+        let RawFrameBuffer { width, height, .. } = bottom.raw_framebuffer();
+        let bottom = self.render_target(width, height, bottom, depth_format)?;
+
+        Ok(render::ScreenTargets {
+            top_left,
+            top_right,
+            bottom,
+        })
+    }
+
     /// Select the given render target for drawing the frame. This must be called
     /// as pare of a render call (i.e. within the call to
     /// [`render_frame_with`](Self::render_frame_with)).
     ///
+    /// If the target has a [default clear](render::Target::with_default_clear),
+    /// it is applied the first time the target is selected in each frame.
+    ///
     /// # Errors
     ///
     /// Fails if the given target cannot be used for drawing, or called outside
     /// the context of a frame render.
     #[doc(alias = "C3D_FrameDrawOn")]
     pub fn select_render_target(&mut self, target: &render::Target<'_>) -> Result<()> {
-        let _ = self;
+        self.counters.state_changes += 1;
         if unsafe { citro3d_sys::C3D_FrameDrawOn(target.as_raw()) } {
+            target.auto_clear(self.frame);
             Ok(())
         } else {
             Err(Error::InvalidRenderTarget)
@@ -135,21 +231,322 @@ impl Instance {
     /// Render a frame. The passed in function/closure can mutate the instance,
     /// such as to [select a render target](Self::select_render_target)
     /// or [bind a new shader program](Self::bind_program).
+    ///
+    /// This waits for the GPU to finish the previous frame first; see
+    /// [`try_render_frame_with`](Self::try_render_frame_with) to configure that.
+    ///
+    /// `f` is not called if the frame could not be started, e.g. when this is
+    /// called from within another frame. This is a bug, and panics in debug
+    /// builds.
     #[doc(alias = "C3D_FrameBegin")]
     #[doc(alias = "C3D_FrameEnd")]
     pub fn render_frame_with(&mut self, f: impl FnOnce(&mut Self)) {
+        let rendered = self.try_render_frame_with(render::FrameConfig::default(), f);
+        debug_assert!(rendered, "failed to begin frame");
+    }
+
+    /// Render a frame like [`render_frame_with`](Self::render_frame_with),
+    /// with the given begin and end flags.
+    ///
+    /// Returns `false` without calling `f` if the frame could not be started,
+    /// e.g. [`FrameFlags::NON_BLOCK`](render::FrameFlags::NON_BLOCK) was set
+    /// and the GPU is still busy with the previous frame. This can be used to
+    /// poll the GPU each iteration of the main loop, and only render when it
+    /// has caught up.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::render::FrameConfig;
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let rendered = instance.try_render_frame_with(FrameConfig::NON_BLOCKING, |_instance| {
+    ///     // draw the scene...
+    /// });
+    ///
+    /// if !rendered {
+    ///     // the GPU is behind, so do something else with this iteration
+    /// }
+    /// ```
+    #[doc(alias = "C3D_FrameBegin")]
+    #[doc(alias = "C3D_FrameEnd")]
+    pub fn try_render_frame_with(
+        &mut self,
+        config: render::FrameConfig,
+        f: impl FnOnce(&mut Self),
+    ) -> bool {
+        if !unsafe { citro3d_sys::C3D_FrameBegin(config.begin.bits()) } {
+            return false;
+        }
+        self.frame = self.frame.wrapping_add(1);
+
+        f(self);
+
+        // Reset per-frame state without counting it as a state change made
+        // by the user
+        if self.scissor {
+            self.reset_scissor();
+        }
+        if self.stencil {
+            self.apply_stencil(None);
+        }
+        if self.blend != render::Blend::default() {
+            self.apply_blend(render::Blend::default());
+        }
+
+        unsafe {
+            citro3d_sys::C3D_FrameEnd(config.end.bits());
+        }
+        self.finish_frame_stats();
+        true
+    }
+
+    /// Submit the commands recorded so far in the current frame to the GPU,
+    /// so it can start drawing them while the CPU records the rest of the
+    /// frame. `flags` control how the commands are submitted, like
+    /// [`FrameConfig::end`](render::FrameConfig::end).
+    ///
+    /// This should be called from within
+    /// [`render_frame_with`](Self::render_frame_with), and does nothing
+    /// outside of a frame.
+    #[doc(alias = "C3D_FrameSplit")]
+    pub fn split_frame(&mut self, flags: render::FrameEndFlags) {
+        unsafe {
+            citro3d_sys::C3D_FrameSplit(flags.bits());
+        }
+    }
+
+    /// Restrict drawing to the given area of the current render target,
+    /// mapping normalized device coordinates onto it.
+    ///
+    /// The viewport is reset to cover the whole target whenever a target is
+    /// [selected](Self::select_render_target).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::render::Rect;
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// // Draw to the left half of the top screen, e.g. for split-screen
+    /// instance.set_viewport(Rect::new(0, 0, 240, 200));
+    /// ```
+    #[doc(alias = "C3D_SetViewport")]
+    pub fn set_viewport(&mut self, rect: render::Rect) {
+        self.counters.state_changes += 1;
+        unsafe {
+            citro3d_sys::C3D_SetViewport(rect.x, rect.y, rect.width, rect.height);
+        }
+    }
+
+    /// Discard fragments inside or outside of the given rectangle for
+    /// subsequent draw calls, e.g. to clip UI elements.
+    ///
+    /// The scissor test is disabled again at the end of the frame, or with
+    /// [`disable_scissor`](Self::disable_scissor).
+    #[doc(alias = "C3D_SetScissor")]
+    pub fn set_scissor(&mut self, mode: render::ScissorMode, rect: render::Rect) {
+        self.counters.state_changes += 1;
         unsafe {
-            citro3d_sys::C3D_FrameBegin(
-                // TODO: begin + end flags should be configurable
-                citro3d_sys::C3D_FRAME_SYNCDRAW,
+            citro3d_sys::C3D_SetScissor(
+                mode as _,
+                rect.x,
+                rect.y,
+                rect.x.saturating_add(rect.width),
+                rect.y.saturating_add(rect.height),
             );
         }
+        self.scissor = true;
+    }
 
-        f(self);
+    /// Disable the scissor test for subsequent draw calls.
+    #[doc(alias = "C3D_SetScissor")]
+    pub fn disable_scissor(&mut self) {
+        self.counters.state_changes += 1;
+        self.reset_scissor();
+    }
+
+    fn reset_scissor(&mut self) {
+        unsafe {
+            citro3d_sys::C3D_SetScissor(ctru_sys::GPU_SCISSOR_DISABLE, 0, 0, 0, 0);
+        }
+        self.scissor = false;
+    }
+
+    /// Configure the stencil test and stencil buffer updates for subsequent
+    /// draw calls, or disable the stencil test with `None`.
+    ///
+    /// The stencil test is disabled again at the end of the frame.
+    #[doc(alias = "C3D_StencilTest")]
+    #[doc(alias = "C3D_StencilOp")]
+    pub fn set_stencil(&mut self, config: Option<render::StencilConfig>) {
+        self.counters.state_changes += 1;
+        self.apply_stencil(config);
+    }
+
+    fn apply_stencil(&mut self, config: Option<render::StencilConfig>) {
+        unsafe {
+            match config {
+                Some(config) => {
+                    citro3d_sys::C3D_StencilTest(
+                        true,
+                        config.function as _,
+                        config.reference.into(),
+                        config.test_mask.into(),
+                        config.write_mask.into(),
+                    );
+                    citro3d_sys::C3D_StencilOp(
+                        config.on_stencil_fail as _,
+                        config.on_depth_fail as _,
+                        config.on_pass as _,
+                    );
+                }
+                None => {
+                    citro3d_sys::C3D_StencilTest(false, ctru_sys::GPU_ALWAYS, 0, 0xFF, 0);
+                    citro3d_sys::C3D_StencilOp(
+                        ctru_sys::GPU_STENCIL_KEEP,
+                        ctru_sys::GPU_STENCIL_KEEP,
+                        ctru_sys::GPU_STENCIL_KEEP,
+                    );
+                }
+            }
+        }
+        self.stencil = config.is_some();
+    }
+
+    /// Set how fragments are blended with the framebuffer for subsequent
+    /// draw calls.
+    ///
+    /// Blending is reset to [`Blend::ALPHA`](render::Blend::ALPHA) at the
+    /// end of the frame.
+    #[doc(alias = "C3D_AlphaBlend")]
+    #[doc(alias = "C3D_BlendingColor")]
+    pub fn set_blend(&mut self, blend: render::Blend) {
+        self.counters.state_changes += 1;
+        self.apply_blend(blend);
+    }
+
+    fn apply_blend(&mut self, blend: render::Blend) {
+        unsafe {
+            citro3d_sys::C3D_AlphaBlend(
+                blend.color_equation as _,
+                blend.alpha_equation as _,
+                blend.src_color as _,
+                blend.dst_color as _,
+                blend.src_alpha as _,
+                blend.dst_alpha as _,
+            );
+            citro3d_sys::C3D_BlendingColor(blend.constant.to_gpu());
+        }
+        self.blend = blend;
+    }
+
+    /// Set how depth values are mapped into the depth buffer for subsequent
+    /// draw calls. See [`render::DepthRange`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::render::DepthRange;
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// // Closer fragments get smaller depth values, like in OpenGL
+    /// instance.set_depth_range(DepthRange::STANDARD);
+    /// ```
+    #[doc(alias = "C3D_DepthMap")]
+    pub fn set_depth_range(&mut self, range: render::DepthRange) {
+        self.counters.state_changes += 1;
+        let (scale, offset) = range.scale_offset();
+        unsafe { citro3d_sys::C3D_DepthMap(true, scale, offset) };
+    }
+
+    /// Render a frame like [`render_frame_with`](Self::render_frame_with), with
+    /// an [`arena::FrameArena`] for transient data such as dynamic vertices.
+    /// The arena is reset at the start of the frame, once the GPU has finished
+    /// with the previous one.
+    #[doc(alias = "C3D_FrameBegin")]
+    #[doc(alias = "C3D_FrameEnd")]
+    pub fn render_frame_with_arena(
+        &mut self,
+        arena: &mut arena::FrameArena,
+        f: impl FnOnce(&mut Self, &arena::FrameArena),
+    ) {
+        self.render_frame_with(|instance| {
+            // Without C3D_FRAME_NONBLOCK, C3D_FrameBegin blocks until the
+            // previous frame's GPU commands have finished, so nothing is
+            // reading from the arena anymore.
+            arena.reset();
+            f(instance, arena);
+        });
+    }
+
+    /// End a frame without rendering anything, so that the previously rendered
+    /// frame stays on screen. This still waits for the GPU and frame timing
+    /// like [`render_frame_with`](Self::render_frame_with) does, but skips any
+    /// clears, draw calls, or display transfers.
+    ///
+    /// This can be useful for pause screens or other static content, to avoid
+    /// re-rendering identical frames at full frame rate.
+    ///
+    /// Returns `false` without doing anything if the frame could not be
+    /// started, e.g. when this is called from within another frame.
+    #[doc(alias = "C3D_FrameBegin")]
+    #[doc(alias = "C3D_FrameEnd")]
+    pub fn present_last_frame(&mut self) -> bool {
+        // No render target is selected between these calls, so citro3d has
+        // nothing to transfer and won't swap the screens' framebuffers.
+        if !unsafe { citro3d_sys::C3D_FrameBegin(render::FrameFlags::SYNC_DRAW.bits()) } {
+            return false;
+        }
 
         unsafe {
             citro3d_sys::C3D_FrameEnd(0);
         }
+        self.finish_frame_stats();
+        true
+    }
+
+    /// Get rendering statistics for the last completed frame, i.e. the last
+    /// call to [`render_frame_with`](Self::render_frame_with) or
+    /// [`present_last_frame`](Self::present_last_frame). Any draw calls or
+    /// state changes made outside of a frame are counted towards the next one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// instance.render_frame_with(|_| {});
+    ///
+    /// let stats = instance.stats();
+    /// assert_eq!(stats.draw_calls, 0);
+    /// ```
+    #[doc(alias = "C3D_GetProcessingTime")]
+    #[doc(alias = "C3D_GetDrawingTime")]
+    #[doc(alias = "C3D_GetCmdBufUsage")]
+    pub fn stats(&self) -> stats::Stats {
+        stats::Stats::new(self.last_frame)
+    }
+
+    fn finish_frame_stats(&mut self) {
+        self.last_frame = std::mem::take(&mut self.counters);
+    }
+
+    /// Get how strongly the stereoscopic 3D effect should be applied, from
+    /// `0.0` to `1.0`. This is the position of the 3D slider, or `0.0` if 3D
+    /// output is not currently enabled for the top screen (e.g. the slider is
+    /// all the way down, or the screen isn't being used as a [`TopScreen3D`]).
+    ///
+    /// This is typically used to scale the interocular distance passed to
+    /// [`StereoDisplacement::new`](math::StereoDisplacement::new).
+    #[doc(alias = "gfxIs3D")]
+    #[doc(alias = "osGet3DSliderState")]
+    pub fn stereo_strength(&self) -> f32 {
+        if unsafe { ctru_sys::gfxIs3D() } {
+            ctru::os::current_3d_slider_state()
+        } else {
+            0.0
+        }
     }
 
     /// Get the buffer info being used, if it exists. Note that the resulting
@@ -164,6 +561,7 @@ impl Instance {
     #[doc(alias = "C3D_SetBufInfo")]
     pub fn set_buffer_info(&mut self, buffer_info: &buffer::Info) {
         let raw: *const _ = &buffer_info.0;
+        self.counters.state_changes += 1;
         // SAFETY: C3D_SetBufInfo actually copies the pointee instead of mutating it.
         unsafe { citro3d_sys::C3D_SetBufInfo(raw.cast_mut()) };
     }
@@ -180,6 +578,7 @@ impl Instance {
     #[doc(alias = "C3D_SetAttrInfo")]
     pub fn set_attr_info(&mut self, attr_info: &attrib::Info) {
         let raw: *const _ = &attr_info.0;
+        self.counters.state_changes += 1;
         // SAFETY: C3D_SetAttrInfo actually copies the pointee instead of mutating it.
         unsafe { citro3d_sys::C3D_SetAttrInfo(raw.cast_mut()) };
     }
@@ -191,17 +590,174 @@ impl Instance {
 
         // TODO: should we also require the attrib info directly here?
 
+        self.draw_arrays_raw(primitive, vbo_data.index(), vbo_data.len());
+    }
+
+    /// Render primitives from a sub-range of the vertices in `vbo_data`. This
+    /// allows drawing part of a large vertex buffer (e.g. a single chunk of a
+    /// mesh) without registering it again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if `range` is not within `vbo_data`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::attrib;
+    /// # use citro3d::buffer::{self, Buffer, Primitive};
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let vbo = Buffer::from_slice(&[[0.0_f32; 3]; 6]).unwrap();
+    /// let mut buf_info = buffer::Info::new();
+    /// let slice = buf_info.add_buffer(&vbo, &attrib::Info::new()).unwrap();
+    ///
+    /// // Draw only the second triangle
+    /// instance
+    ///     .draw_arrays_range(Primitive::Triangles, slice, 3..6)
+    ///     .unwrap();
+    /// assert!(instance
+    ///     .draw_arrays_range(Primitive::Triangles, slice, 3..9)
+    ///     .is_err());
+    /// ```
+    #[doc(alias = "C3D_DrawArrays")]
+    pub fn draw_arrays_range(
+        &mut self,
+        primitive: buffer::Primitive,
+        vbo_data: buffer::Slice,
+        range: Range<usize>,
+    ) -> Result<()> {
+        let len = usize::try_from(vbo_data.len())?;
+        if range.start > range.end || range.end > len {
+            return Err(Error::InvalidSize);
+        }
+
+        self.set_buffer_info(vbo_data.info());
+
+        // `first` is a vertex index, not the buffer's slot in the BufInfo
+        let first = libc::c_int::try_from(range.start)?;
+        let count = libc::c_int::try_from(range.len())?;
+        self.draw_arrays_raw(primitive, first, count);
+        Ok(())
+    }
+
+    /// Render primitives from `vbo_data`, using `indices` to select the
+    /// vertex for each element. Each index refers to a vertex relative to the
+    /// start of `vbo_data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if there are too many indices to draw in
+    /// a single call.
+    #[doc(alias = "C3D_DrawElements")]
+    pub fn draw_elements<I: buffer::IndexType, A: buffer::GpuVisibleAlloc>(
+        &mut self,
+        primitive: buffer::Primitive,
+        vbo_data: buffer::Slice,
+        indices: &buffer::Buffer<I, A>,
+    ) -> Result<()> {
+        let count = libc::c_int::try_from(indices.len())?;
+
+        self.set_buffer_info(vbo_data.info());
+        self.apply_texenvs();
+        self.counters.record_draw(primitive, indices.len());
+
+        // SAFETY: `GpuVisibleAlloc` guarantees the indices are in memory which
+        // citro3d can convert to a physical address for the GPU.
         unsafe {
-            citro3d_sys::C3D_DrawArrays(
+            citro3d_sys::C3D_DrawElements(
                 primitive as ctru_sys::GPU_Primitive_t,
-                vbo_data.index(),
-                vbo_data.len(),
+                count,
+                I::RAW,
+                indices.as_ptr().cast(),
             );
         }
+        Ok(())
+    }
+
+    /// Draw a [`Mesh`](mesh::Mesh), setting up its vertex buffer and attribute
+    /// info, and using its index buffer if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the mesh's vertices could not be registered, or if it has too
+    /// many indices to draw in a single call.
+    pub fn draw_mesh<V, I: buffer::IndexType>(&mut self, mesh: &mesh::Mesh<V, I>) -> Result<()> {
+        let mut buf_info = buffer::Info::new();
+        let vbo_data = mesh.register(&mut buf_info)?;
+        self.set_attr_info(mesh.attrib_info());
+
+        match mesh.index_buffer() {
+            Some(indices) => self.draw_elements(mesh.primitive(), vbo_data, indices),
+            None => {
+                self.draw_arrays(mesh.primitive(), vbo_data);
+                Ok(())
+            }
+        }
+    }
+
+    fn draw_arrays_raw(
+        &mut self,
+        primitive: buffer::Primitive,
+        first: libc::c_int,
+        count: libc::c_int,
+    ) {
+        self.apply_texenvs();
+        self.counters
+            .record_draw(primitive, count.try_into().unwrap_or(0));
+
+        unsafe {
+            citro3d_sys::C3D_DrawArrays(primitive as ctru_sys::GPU_Primitive_t, first, count);
+        }
+    }
+
+    /// Render primitives in immediate mode, sending each vertex's attributes
+    /// directly in the command buffer instead of reading them from a vertex
+    /// buffer. This is convenient for debug overlays or small meshes that
+    /// change every frame, but is slower than [`draw_arrays`](Self::draw_arrays)
+    /// for larger amounts of data.
+    ///
+    /// The attributes sent by `f` are interpreted using the current
+    /// [attribute info](Self::set_attr_info).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::buffer::Primitive;
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// // With a single position attribute configured:
+    /// instance.immediate(Primitive::Triangles, |vtx| {
+    ///     vtx.vertex([[-0.5, -0.5, 0.5, 1.0]]);
+    ///     vtx.vertex([[0.5, -0.5, 0.5, 1.0]]);
+    ///     vtx.vertex([[0.0, 0.5, 0.5, 1.0]]);
+    /// });
+    /// ```
+    #[doc(alias = "C3D_ImmDrawBegin")]
+    #[doc(alias = "C3D_ImmDrawEnd")]
+    pub fn immediate(
+        &mut self,
+        primitive: buffer::Primitive,
+        f: impl FnOnce(&mut buffer::Immediate),
+    ) {
+        self.apply_texenvs();
+        unsafe { citro3d_sys::C3D_ImmDrawBegin(primitive as ctru_sys::GPU_Primitive_t) };
+        let mut immediate = buffer::Immediate::new();
+        f(&mut immediate);
+        unsafe { citro3d_sys::C3D_ImmDrawEnd() };
+
+        let attrs_per_vertex = self
+            .attr_info()
+            .and_then(|info| usize::try_from(info.attr_count()).ok())
+            .filter(|&count| count > 0)
+            .unwrap_or(1);
+        self.counters
+            .record_draw(primitive, immediate.attrib_count() / attrs_per_vertex);
     }
 
     /// Use the given [`shader::Program`] for subsequent draw calls.
     pub fn bind_program(&mut self, program: &shader::Program) {
+        self.counters.state_changes += 1;
         // SAFETY: AFAICT C3D_BindProgram just copies pointers from the given program,
         // instead of mutating the pointee in any way that would cause UB
         unsafe {
@@ -209,7 +765,107 @@ impl Instance {
         }
     }
 
+    /// Use the given procedural texture for subsequent draw calls, generating
+    /// texels from texture coordinate `tex_coord` (0, 1, or 2). Its output
+    /// can be used in texture combiners as
+    /// [`Source::Texture3`](texenv::Source::Texture3).
+    ///
+    /// The instance keeps a copy of `proctex`, so it can be dropped or changed
+    /// afterwards without affecting rendering until it is bound again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if `tex_coord` is out of range.
+    #[doc(alias = "C3D_ProcTexBind")]
+    #[doc(alias = "C3D_ProcTexLutBind")]
+    #[doc(alias = "C3D_ProcTexColorLutBind")]
+    pub fn bind_proctex(&mut self, tex_coord: usize, proctex: &proctex::ProcTex) -> Result<()> {
+        if tex_coord > 2 {
+            return Err(Error::InvalidSize);
+        }
+
+        let mut bound = Box::new(proctex.clone());
+        self.counters.state_changes += 1;
+
+        let lut_ptr = |lut: &mut Option<proctex::Lut>| {
+            lut.as_mut()
+                .map_or(std::ptr::null_mut(), |lut| &mut lut.0 as *mut _)
+        };
+
+        // SAFETY: the pointers passed to citro3d point into `bound`, which is
+        // kept alive by the instance until another procedural texture is bound.
+        unsafe {
+            citro3d_sys::C3D_ProcTexBind(tex_coord as _, &mut bound.raw);
+            citro3d_sys::C3D_ProcTexLutBind(ctru_sys::GPU_LUT_NOISE, lut_ptr(&mut bound.noise));
+            citro3d_sys::C3D_ProcTexLutBind(ctru_sys::GPU_LUT_RGBMAP, lut_ptr(&mut bound.rgb_map));
+            citro3d_sys::C3D_ProcTexLutBind(
+                ctru_sys::GPU_LUT_ALPHAMAP,
+                lut_ptr(&mut bound.alpha_map),
+            );
+            citro3d_sys::C3D_ProcTexColorLutBind(&mut bound.colors.raw);
+        }
+
+        self.proctex = Some(bound);
+        Ok(())
+    }
+
+    /// Disable the procedural texture unit for subsequent draw calls.
+    #[doc(alias = "C3D_ProcTexBind")]
+    pub fn unbind_proctex(&mut self) {
+        self.counters.state_changes += 1;
+        unsafe {
+            citro3d_sys::C3D_ProcTexBind(0, std::ptr::null_mut());
+            citro3d_sys::C3D_ProcTexLutBind(ctru_sys::GPU_LUT_NOISE, std::ptr::null_mut());
+            citro3d_sys::C3D_ProcTexLutBind(ctru_sys::GPU_LUT_RGBMAP, std::ptr::null_mut());
+            citro3d_sys::C3D_ProcTexLutBind(ctru_sys::GPU_LUT_ALPHAMAP, std::ptr::null_mut());
+            citro3d_sys::C3D_ProcTexColorLutBind(std::ptr::null_mut());
+        }
+        self.proctex = None;
+    }
+
+    /// Enable fog for subsequent draw calls, or disable it with `None`.
+    ///
+    /// The instance keeps a copy of `fog`, so it can be dropped or changed
+    /// afterwards without affecting rendering until it is set again.
+    #[doc(alias = "C3D_FogGasMode")]
+    #[doc(alias = "C3D_FogColor")]
+    #[doc(alias = "C3D_FogLutBind")]
+    pub fn set_fog(&mut self, fog: Option<&fog::Fog>) {
+        self.counters.state_changes += 1;
+
+        let Some(fog) = fog else {
+            unsafe {
+                citro3d_sys::C3D_FogGasMode(
+                    ctru_sys::GPU_NO_FOG,
+                    ctru_sys::GPU_PLAIN_DENSITY,
+                    false,
+                );
+                citro3d_sys::C3D_FogLutBind(std::ptr::null_mut());
+            }
+            self.fog = None;
+            return;
+        };
+
+        let mut bound = Box::new(fog.clone());
+
+        // SAFETY: the LUT pointer passed to citro3d points into `bound`, which
+        // is kept alive by the instance until fog is set again.
+        unsafe {
+            citro3d_sys::C3D_FogGasMode(
+                ctru_sys::GPU_FOG,
+                ctru_sys::GPU_PLAIN_DENSITY,
+                bound.flip_depth,
+            );
+            citro3d_sys::C3D_FogColor(bound.color.to_gpu());
+            citro3d_sys::C3D_FogLutBind(&mut bound.lut.0);
+        }
+
+        self.fog = Some(bound);
+    }
+
     /// Bind a uniform to the given `index` in the vertex shader for the next draw call.
+    /// Registers which already hold the given value are skipped, so rebinding
+    /// e.g. the same projection matrix for every draw call does not re-upload it.
     ///
     /// # Example
     ///
@@ -245,7 +901,117 @@ impl Instance {
         uniform.into().bind(self, shader::Type::Geometry, index);
     }
 
+    /// Bind a uniform to the given `range` of registers in the vertex shader for
+    /// the next draw call, checking that it fits within the range first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if the uniform does not fit in `range`,
+    /// i.e. it is too large or uses a different kind of register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::macros::include_shader;
+    /// # use citro3d::math::Matrix4;
+    /// # use citro3d::shader;
+    /// # static SHADER_BYTES: &[u8] = include_shader!("../examples/assets/vshader.pica");
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let library = shader::Library::from_bytes(SHADER_BYTES).unwrap();
+    /// let program = shader::Program::new(library.get(0).unwrap()).unwrap();
+    ///
+    /// let projection = program.get_uniform_range("projection").unwrap();
+    /// instance
+    ///     .bind_vertex_uniform_range(projection, Matrix4::identity())
+    ///     .unwrap();
+    /// ```
+    pub fn bind_vertex_uniform_range(
+        &mut self,
+        range: uniform::IndexRange,
+        uniform: impl Into<Uniform>,
+    ) -> Result<()> {
+        let uniform = uniform.into();
+        if !range.fits(&uniform) {
+            return Err(Error::InvalidSize);
+        }
+        self.bind_vertex_uniform(range.start(), uniform);
+        Ok(())
+    }
+
+    /// Bind a uniform to the given `range` of registers in the geometry shader
+    /// for the next draw call, checking that it fits within the range first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if the uniform does not fit in `range`,
+    /// i.e. it is too large or uses a different kind of register.
+    pub fn bind_geometry_uniform_range(
+        &mut self,
+        range: uniform::IndexRange,
+        uniform: impl Into<Uniform>,
+    ) -> Result<()> {
+        let uniform = uniform.into();
+        if !range.fits(&uniform) {
+            return Err(Error::InvalidSize);
+        }
+        self.bind_geometry_uniform(range.start(), uniform);
+        Ok(())
+    }
+
+    /// Bind a boolean uniform to the given `index` in the vertex shader for the
+    /// next draw call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::uniform;
+    /// #
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let idx = uniform::BoolIndex::new(0).unwrap();
+    /// instance.bind_vertex_uniform_bool(idx, true);
+    /// ```
+    #[doc(alias = "C3D_BoolUnifSet")]
+    pub fn bind_vertex_uniform_bool(&mut self, index: uniform::BoolIndex, value: bool) {
+        uniform::bind_bool(shader::Type::Vertex, index, value);
+    }
+
+    /// Bind a boolean uniform to the given `index` in the geometry shader for
+    /// the next draw call.
+    #[doc(alias = "C3D_BoolUnifSet")]
+    pub fn bind_geometry_uniform_bool(&mut self, index: uniform::BoolIndex, value: bool) {
+        uniform::bind_bool(shader::Type::Geometry, index, value);
+    }
+
+    /// Bind an integer uniform to the given `index` in the vertex shader for the
+    /// next draw call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::uniform;
+    /// # use citro3d::math::IVec;
+    /// #
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let idx = uniform::IntIndex::new(0).unwrap();
+    /// instance.bind_vertex_uniform_int(idx, IVec::new(4, 0, 1, 0));
+    /// ```
+    #[doc(alias = "C3D_IVUnifSet")]
+    pub fn bind_vertex_uniform_int(&mut self, index: uniform::IntIndex, value: math::IVec) {
+        uniform::bind_int(shader::Type::Vertex, index, value);
+    }
+
+    /// Bind an integer uniform to the given `index` in the geometry shader for
+    /// the next draw call.
+    #[doc(alias = "C3D_IVUnifSet")]
+    pub fn bind_geometry_uniform_int(&mut self, index: uniform::IntIndex, value: math::IVec) {
+        uniform::bind_int(shader::Type::Geometry, index, value);
+    }
+
     /// Retrieve the [`TexEnv`] for the given stage, initializing it first if necessary.
+    /// Changes take effect at the next draw call.
     ///
     /// # Example
     ///
@@ -257,13 +1023,90 @@ impl Instance {
     /// let texenv0 = instance.texenv(stage0);
     /// ```
     #[doc(alias = "C3D_GetTexEnv")]
+    #[doc(alias = "C3D_SetTexEnv")]
     #[doc(alias = "C3D_TexEnvInit")]
     pub fn texenv(&mut self, stage: texenv::Stage) -> &mut texenv::TexEnv {
-        let texenv = &mut self.texenvs[stage.0];
-        texenv.get_or_init(|| TexEnv::new(stage));
-        // We have to do this weird unwrap to get a mutable reference,
-        // since there is no `get_mut_or_init` or equivalent
-        texenv.get_mut().unwrap()
+        texenv::get_or_init(&mut self.texenvs[stage.0])
+    }
+
+    /// Choose which texture combiner stages write their output to the
+    /// combiner buffer, separately for colors and alpha values depending on
+    /// `mode`. Without this, [`Source::PreviousBuffer`](texenv::Source::PreviousBuffer)
+    /// only ever yields the [initial buffer color](Self::set_texenv_buffer_color).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::texenv::{BufferWrites, Mode};
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// // Keep stage 0's color around for stages after stage 1 to read
+    /// instance.set_texenv_buffer(Mode::RGB, BufferWrites::STAGE0);
+    /// instance.set_texenv_buffer(Mode::ALPHA, BufferWrites::empty());
+    /// ```
+    #[doc(alias = "C3D_TexEnvBufUpdate")]
+    pub fn set_texenv_buffer(&mut self, mode: texenv::Mode, writes: texenv::BufferWrites) {
+        self.counters.texenv_updates += 1;
+        unsafe { citro3d_sys::C3D_TexEnvBufUpdate(mode.bits() as _, writes.bits().into()) };
+    }
+
+    /// Set the color the combiner buffer holds before any stage writes to it.
+    #[doc(alias = "C3D_TexEnvBufColor")]
+    pub fn set_texenv_buffer_color(&mut self, color: color::Color) {
+        self.counters.texenv_updates += 1;
+        unsafe { citro3d_sys::C3D_TexEnvBufColor(color.to_gpu()) };
+    }
+
+    /// Configure every texture combiner stage at once. Stages which aren't
+    /// configured by `f` are reset to pass their input through unchanged, so
+    /// state left over from previous draw calls can't affect the next ones.
+    /// Use [`TexEnvs::keep_unused`](texenv::TexEnvs::keep_unused) to opt out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::texenv::{self, CombineFunc, Mode, Source};
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// instance.set_texenvs(|texenvs| {
+    ///     // Stages 1 to 5 are reset to pass through stage 0's output
+    ///     texenvs
+    ///         .stage(texenv::Stage::new(0).unwrap())
+    ///         .src(Mode::BOTH, Source::PrimaryColor, None, None)
+    ///         .func(Mode::BOTH, CombineFunc::Replace);
+    /// });
+    /// ```
+    #[doc(alias = "C3D_GetTexEnv")]
+    #[doc(alias = "C3D_TexEnvInit")]
+    pub fn set_texenvs(&mut self, f: impl FnOnce(&mut texenv::TexEnvs<'_>)) {
+        let mut texenvs = texenv::TexEnvs::new(&mut self.texenvs);
+        f(&mut texenvs);
+        texenvs.finish();
+    }
+
+    /// Send any texture combiners which changed since the last draw call to
+    /// the GPU, skipping those identical to what the GPU already has.
+    #[doc(alias = "C3D_SetTexEnv")]
+    fn apply_texenvs(&mut self) {
+        for (index, (texenv, applied)) in self
+            .texenvs
+            .iter()
+            .zip(&mut self.applied_texenvs)
+            .enumerate()
+        {
+            let Some(texenv) = texenv.get() else {
+                continue;
+            };
+            if applied.as_ref() == Some(texenv) {
+                continue;
+            }
+
+            let raw: *const _ = &texenv.raw;
+            // SAFETY: C3D_SetTexEnv copies the pointee instead of mutating it.
+            unsafe { citro3d_sys::C3D_SetTexEnv(index as _, raw.cast_mut()) };
+            *applied = Some(texenv.clone());
+            self.counters.texenv_updates += 1;
+        }
     }
 }
 
@@ -3,11 +3,37 @@
 // TODO: bench FFI calls into `inline statics` generated by bindgen, vs
 // reimplementing some of those calls. Many of them are pretty trivial impls
 
+mod batch;
 mod fvec;
 mod matrix;
 mod ops;
 mod projection;
+#[cfg(feature = "pure-rust-math")]
+mod soft;
 
+/// The `citro3d` math functions used to implement this module, which can be
+/// replaced by pure-Rust implementations with the `pure-rust-math` feature.
+mod sys {
+    #[cfg(not(feature = "pure-rust-math"))]
+    pub(crate) use citro3d_sys::{
+        FVec3_Add, FVec3_Cross, FVec3_Distance, FVec3_Dot, FVec3_Magnitude, FVec3_Negate,
+        FVec3_New, FVec3_Normalize, FVec3_Scale, FVec3_Subtract, FVec4_Add, FVec4_Dot,
+        FVec4_Magnitude, FVec4_Negate, FVec4_New, FVec4_Normalize, FVec4_PerspDivide, FVec4_Scale,
+        FVec4_Subtract, Mtx_Add, Mtx_Diagonal, Mtx_Identity, Mtx_Multiply, Mtx_MultiplyFVec4,
+        Mtx_MultiplyFVecH, Mtx_Scale, Mtx_Subtract, Mtx_Translate, Mtx_Transpose, Mtx_Zeros,
+    };
+
+    #[cfg(feature = "pure-rust-math")]
+    pub(crate) use super::soft::{
+        FVec3_Add, FVec3_Cross, FVec3_Distance, FVec3_Dot, FVec3_Magnitude, FVec3_Negate,
+        FVec3_New, FVec3_Normalize, FVec3_Scale, FVec3_Subtract, FVec4_Add, FVec4_Dot,
+        FVec4_Magnitude, FVec4_Negate, FVec4_New, FVec4_Normalize, FVec4_PerspDivide, FVec4_Scale,
+        FVec4_Subtract, Mtx_Add, Mtx_Diagonal, Mtx_Identity, Mtx_Multiply, Mtx_MultiplyFVec4,
+        Mtx_MultiplyFVecH, Mtx_Scale, Mtx_Subtract, Mtx_Translate, Mtx_Transpose, Mtx_Zeros,
+    };
+}
+
+pub use batch::{multiply_matrices, transform_points};
 pub use fvec::{FVec, FVec3, FVec4};
 pub use matrix::Matrix4;
 pub use projection::{
@@ -0,0 +1,112 @@
+//! Batch operations on slices of vectors and matrices.
+//!
+//! These are implemented in Rust rather than calling into `citro3d` once per
+//! element, which avoids the FFI overhead in hot loops and lets the compiler
+//! keep the shared matrix in VFP registers for the whole batch.
+
+use super::{FVec3, Matrix4};
+
+/// Transform each point in `points` by the given affine transformation matrix,
+/// in place. Each point is treated as having `w = 1`, and the `w` component of
+/// the result is discarded.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::math::{self, FVec3, Matrix4};
+/// # use approx::assert_abs_diff_eq;
+/// let mut transform = Matrix4::identity();
+/// transform.translate(1.0, 2.0, 3.0);
+///
+/// let mut points = [FVec3::splat(0.0), FVec3::splat(1.0)];
+/// math::transform_points(&transform, &mut points);
+///
+/// assert_abs_diff_eq!(points[0], FVec3::new(1.0, 2.0, 3.0));
+/// assert_abs_diff_eq!(points[1], FVec3::new(2.0, 3.0, 4.0));
+/// ```
+#[doc(alias = "Mtx_MultiplyFVecH")]
+pub fn transform_points(transform: &Matrix4, points: &mut [FVec3]) {
+    let [r0, r1, r2, _] = transform.rows_xyzw();
+    let dot = |r: [f32; 4], x: f32, y: f32, z: f32| r[0] * x + r[1] * y + r[2] * z + r[3];
+
+    for point in points {
+        let (x, y, z) = (point.x(), point.y(), point.z());
+        point.0 = citro3d_sys::C3D_FVec {
+            c: [0.0, dot(r2, x, y, z), dot(r1, x, y, z), dot(r0, x, y, z)],
+        };
+    }
+}
+
+/// Multiply each matrix in `matrices` by `lhs`, in place, i.e. replace each
+/// matrix `m` with `lhs * m`. This is useful for e.g. applying the same view
+/// matrix to the model matrices of many objects.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::math::{self, Matrix4};
+/// # use approx::assert_abs_diff_eq;
+/// let view = Matrix4::diagonal(2.0, 2.0, 2.0, 1.0);
+/// let mut models = [Matrix4::identity(), Matrix4::diagonal(1.0, 2.0, 3.0, 1.0)];
+/// math::multiply_matrices(&view, &mut models);
+///
+/// assert_abs_diff_eq!(models[0], view);
+/// assert_abs_diff_eq!(models[1], Matrix4::diagonal(2.0, 4.0, 6.0, 1.0));
+/// ```
+#[doc(alias = "Mtx_Multiply")]
+pub fn multiply_matrices(lhs: &Matrix4, matrices: &mut [Matrix4]) {
+    let a = lhs.rows_xyzw();
+
+    for matrix in matrices {
+        let b = matrix.rows_xyzw();
+        let rows = a.map(|row| {
+            std::array::from_fn(|i| {
+                row[0] * b[0][i] + row[1] * b[1][i] + row[2] * b[2][i] + row[3] * b[3][i]
+            })
+        });
+        *matrix = Matrix4::from_rows_xyzw(rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    fn sample_transform() -> Matrix4 {
+        let mut mtx = Matrix4::identity();
+        mtx.rotate_y(0.5);
+        mtx.scale(1.0, 2.0, 3.0);
+        mtx.translate(-1.0, 4.0, 0.5);
+        mtx
+    }
+
+    #[test]
+    fn transform_points_matches_multiply() {
+        let transform = sample_transform();
+        let mut points = [FVec3::new(1.0, 2.0, 3.0), FVec3::new(-4.0, 0.5, 0.0)];
+        let expected = points.map(|p| &transform * p);
+
+        transform_points(&transform, &mut points);
+
+        for (actual, expected) in points.into_iter().zip(expected) {
+            assert_abs_diff_eq!(actual, FVec3::new(expected.x(), expected.y(), expected.z()));
+        }
+    }
+
+    #[test]
+    fn multiply_matrices_matches_multiply() {
+        let lhs = sample_transform();
+        let mut matrices = [Matrix4::identity(), sample_transform().transpose()];
+        let expected = matrices.map(|m| lhs * m);
+
+        multiply_matrices(&lhs, &mut matrices);
+
+        for (actual, expected) in matrices.into_iter().zip(expected) {
+            assert_abs_diff_eq!(actual, expected);
+        }
+    }
+}
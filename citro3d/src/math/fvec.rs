@@ -2,6 +2,8 @@
 
 use std::fmt;
 
+use super::sys;
+
 /// A vector of `f32`s.
 ///
 /// # Layout
@@ -70,7 +72,7 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_New")]
     pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
-        Self(unsafe { citro3d_sys::FVec4_New(x, y, z, w) })
+        Self(unsafe { sys::FVec4_New(x, y, z, w) })
     }
 
     /// Create a new [`FVec4`], setting each component to `v`.
@@ -99,7 +101,7 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_PerspDivide")]
     pub fn perspective_divide(self) -> Self {
-        Self(unsafe { citro3d_sys::FVec4_PerspDivide(self.0) })
+        Self(unsafe { sys::FVec4_PerspDivide(self.0) })
     }
 
     /// The dot product of two vectors.
@@ -115,7 +117,7 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_Dot")]
     pub fn dot(self, rhs: Self) -> f32 {
-        unsafe { citro3d_sys::FVec4_Dot(self.0, rhs.0) }
+        unsafe { sys::FVec4_Dot(self.0, rhs.0) }
     }
 
     /// The magnitude of the vector.
@@ -130,7 +132,7 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_Magnitude")]
     pub fn magnitude(self) -> f32 {
-        unsafe { citro3d_sys::FVec4_Magnitude(self.0) }
+        unsafe { sys::FVec4_Magnitude(self.0) }
     }
 
     /// Normalize the vector to a magnitude of `1.0`.
@@ -145,7 +147,7 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_Normalize")]
     pub fn normalize(self) -> Self {
-        Self(unsafe { citro3d_sys::FVec4_Normalize(self.0) })
+        Self(unsafe { sys::FVec4_Normalize(self.0) })
     }
 }
 
@@ -160,7 +162,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_New")]
     pub fn new(x: f32, y: f32, z: f32) -> Self {
-        Self(unsafe { citro3d_sys::FVec3_New(x, y, z) })
+        Self(unsafe { sys::FVec3_New(x, y, z) })
     }
 
     /// Create a new [`FVec3`], setting each component to the given `v`.
@@ -189,7 +191,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Distance")]
     pub fn distance(self, rhs: Self) -> f32 {
-        unsafe { citro3d_sys::FVec3_Distance(self.0, rhs.0) }
+        unsafe { sys::FVec3_Distance(self.0, rhs.0) }
     }
 
     /// The cross product of two 3D vectors.
@@ -205,7 +207,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Cross")]
     pub fn cross(self, rhs: Self) -> Self {
-        Self(unsafe { citro3d_sys::FVec3_Cross(self.0, rhs.0) })
+        Self(unsafe { sys::FVec3_Cross(self.0, rhs.0) })
     }
 
     /// The dot product of two vectors.
@@ -221,7 +223,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Dot")]
     pub fn dot(self, rhs: Self) -> f32 {
-        unsafe { citro3d_sys::FVec3_Dot(self.0, rhs.0) }
+        unsafe { sys::FVec3_Dot(self.0, rhs.0) }
     }
 
     /// The magnitude of the vector.
@@ -236,7 +238,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Magnitude")]
     pub fn magnitude(self) -> f32 {
-        unsafe { citro3d_sys::FVec3_Magnitude(self.0) }
+        unsafe { sys::FVec3_Magnitude(self.0) }
     }
 
     /// Normalize the vector to a magnitude of `1.0`.
@@ -251,7 +253,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Normalize")]
     pub fn normalize(self) -> Self {
-        Self(unsafe { citro3d_sys::FVec3_Normalize(self.0) })
+        Self(unsafe { sys::FVec3_Normalize(self.0) })
     }
 }
 
@@ -1,6 +1,6 @@
 use std::mem::MaybeUninit;
 
-use super::{CoordinateOrientation, FVec3, FVec4};
+use super::{sys, CoordinateOrientation, FVec3, FVec4};
 
 /// A 4x4 row-major matrix of `f32`s.
 ///
@@ -20,9 +20,24 @@ impl Matrix4 {
     ///
     /// # Note
     /// This expects rows to be in WZYX order
-    pub fn from_cells_wzyx(cells: [f32; 16]) -> Self {
+    pub const fn from_cells_wzyx(cells: [f32; 16]) -> Self {
         Self(citro3d_sys::C3D_Mtx { m: cells })
     }
+    /// Construct a Matrix4 from its rows, given in XYZW order. Unlike
+    /// [`Matrix4::from_rows`], this can be used in `const` contexts.
+    pub const fn from_rows_xyzw(rows: [[f32; 4]; 4]) -> Self {
+        let mut cells = [0.0; 16];
+        let mut i = 0;
+        while i < 4 {
+            let [x, y, z, w] = rows[i];
+            cells[4 * i] = w;
+            cells[4 * i + 1] = z;
+            cells[4 * i + 2] = y;
+            cells[4 * i + 3] = x;
+            i += 1;
+        }
+        Self::from_cells_wzyx(cells)
+    }
     /// Construct a Matrix4 from its rows
     pub fn from_rows(rows: [FVec4; 4]) -> Self {
         Self(citro3d_sys::C3D_Mtx {
@@ -61,7 +76,7 @@ impl Matrix4 {
         // TODO: should this also be Default::default()?
         let mut out = MaybeUninit::uninit();
         unsafe {
-            citro3d_sys::Mtx_Zeros(out.as_mut_ptr());
+            sys::Mtx_Zeros(out.as_mut_ptr());
             Self::from_raw(out.assume_init())
         }
     }
@@ -70,7 +85,7 @@ impl Matrix4 {
     #[doc(alias = "Mtx_Transpose")]
     pub fn transpose(mut self) -> Matrix4 {
         unsafe {
-            citro3d_sys::Mtx_Transpose(self.as_raw_mut());
+            sys::Mtx_Transpose(self.as_raw_mut());
         }
         Matrix4::from_raw(self.into_raw())
     }
@@ -87,13 +102,13 @@ impl Matrix4 {
     /// directions.
     #[doc(alias = "Mtx_Translate")]
     pub fn translate(&mut self, x: f32, y: f32, z: f32) {
-        unsafe { citro3d_sys::Mtx_Translate(self.as_raw_mut(), x, y, z, false) }
+        unsafe { sys::Mtx_Translate(self.as_raw_mut(), x, y, z, false) }
     }
 
     /// Scale a transformation matrix by the given amounts in the X, Y, and Z directions.
     #[doc(alias = "Mtx_Scale")]
     pub fn scale(&mut self, x: f32, y: f32, z: f32) {
-        unsafe { citro3d_sys::Mtx_Scale(self.as_raw_mut(), x, y, z) }
+        unsafe { sys::Mtx_Scale(self.as_raw_mut(), x, y, z) }
     }
 
     /// Rotate a transformation matrix by the given angle around the given axis.
@@ -140,7 +155,7 @@ impl Matrix4 {
     pub fn identity() -> Self {
         let mut out = MaybeUninit::uninit();
         unsafe {
-            citro3d_sys::Mtx_Identity(out.as_mut_ptr());
+            sys::Mtx_Identity(out.as_mut_ptr());
             Self::from_raw(out.assume_init())
         }
     }
@@ -150,7 +165,7 @@ impl Matrix4 {
     pub fn diagonal(x: f32, y: f32, z: f32, w: f32) -> Self {
         let mut out = MaybeUninit::uninit();
         unsafe {
-            citro3d_sys::Mtx_Diagonal(out.as_mut_ptr(), x, y, z, w);
+            sys::Mtx_Diagonal(out.as_mut_ptr(), x, y, z, w);
             Self::from_raw(out.assume_init())
         }
     }
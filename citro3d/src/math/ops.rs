@@ -4,7 +4,7 @@ use std::ops::{Add, Div, Mul, Neg, Sub};
 #[cfg(feature = "approx")]
 use approx::AbsDiffEq;
 
-use super::{FVec, FVec3, FVec4, Matrix4};
+use super::{sys, FVec, FVec3, FVec4, Matrix4};
 
 // region: FVec4 math operators
 
@@ -13,7 +13,7 @@ impl Add for FVec4 {
 
     #[doc(alias = "FVec4_Add")]
     fn add(self, rhs: Self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec4_Add(self.0, rhs.0) })
+        Self(unsafe { sys::FVec4_Add(self.0, rhs.0) })
     }
 }
 
@@ -22,7 +22,7 @@ impl Sub for FVec4 {
 
     #[doc(alias = "FVec4_Subtract")]
     fn sub(self, rhs: Self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec4_Subtract(self.0, rhs.0) })
+        Self(unsafe { sys::FVec4_Subtract(self.0, rhs.0) })
     }
 }
 
@@ -31,7 +31,7 @@ impl Neg for FVec4 {
 
     #[doc(alias = "FVec4_Negate")]
     fn neg(self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec4_Negate(self.0) })
+        Self(unsafe { sys::FVec4_Negate(self.0) })
     }
 }
 
@@ -40,7 +40,7 @@ impl Mul<f32> for FVec4 {
 
     #[doc(alias = "FVec4_Scale")]
     fn mul(self, rhs: f32) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec4_Scale(self.0, rhs) })
+        Self(unsafe { sys::FVec4_Scale(self.0, rhs) })
     }
 }
 
@@ -53,7 +53,7 @@ impl Add for FVec3 {
 
     #[doc(alias = "FVec3_Add")]
     fn add(self, rhs: Self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec3_Add(self.0, rhs.0) })
+        Self(unsafe { sys::FVec3_Add(self.0, rhs.0) })
     }
 }
 
@@ -62,7 +62,7 @@ impl Sub for FVec3 {
 
     #[doc(alias = "FVec3_Subtract")]
     fn sub(self, rhs: Self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec3_Subtract(self.0, rhs.0) })
+        Self(unsafe { sys::FVec3_Subtract(self.0, rhs.0) })
     }
 }
 
@@ -71,7 +71,7 @@ impl Neg for FVec3 {
 
     #[doc(alias = "FVec3_Negate")]
     fn neg(self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec3_Negate(self.0) })
+        Self(unsafe { sys::FVec3_Negate(self.0) })
     }
 }
 
@@ -80,7 +80,7 @@ impl Mul<f32> for FVec3 {
 
     #[doc(alias = "FVec3_Scale")]
     fn mul(self, rhs: f32) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec3_Scale(self.0, rhs) })
+        Self(unsafe { sys::FVec3_Scale(self.0, rhs) })
     }
 }
 
@@ -132,7 +132,7 @@ impl Add<Matrix4> for Matrix4 {
     fn add(self, rhs: Matrix4) -> Self::Output {
         let mut out = MaybeUninit::uninit();
         unsafe {
-            citro3d_sys::Mtx_Add(out.as_mut_ptr(), self.as_raw(), rhs.as_raw());
+            sys::Mtx_Add(out.as_mut_ptr(), self.as_raw(), rhs.as_raw());
             Matrix4::from_raw(out.assume_init())
         }
     }
@@ -145,7 +145,7 @@ impl Sub<Matrix4> for Matrix4 {
     fn sub(self, rhs: Matrix4) -> Self::Output {
         let mut out = MaybeUninit::uninit();
         unsafe {
-            citro3d_sys::Mtx_Subtract(out.as_mut_ptr(), self.as_raw(), rhs.as_raw());
+            sys::Mtx_Subtract(out.as_mut_ptr(), self.as_raw(), rhs.as_raw());
             Matrix4::from_raw(out.assume_init())
         }
     }
@@ -158,7 +158,7 @@ impl Mul<Matrix4> for Matrix4 {
     fn mul(self, rhs: Matrix4) -> Self::Output {
         let mut out = MaybeUninit::uninit();
         unsafe {
-            citro3d_sys::Mtx_Multiply(out.as_mut_ptr(), self.as_raw(), rhs.as_raw());
+            sys::Mtx_Multiply(out.as_mut_ptr(), self.as_raw(), rhs.as_raw());
             Matrix4::from_raw(out.assume_init())
         }
     }
@@ -177,7 +177,7 @@ impl Mul<FVec4> for &Matrix4 {
 
     #[doc(alias = "Mtx_MultiplyFVec4")]
     fn mul(self, rhs: FVec4) -> Self::Output {
-        FVec(unsafe { citro3d_sys::Mtx_MultiplyFVec4(self.as_raw(), rhs.0) })
+        FVec(unsafe { sys::Mtx_MultiplyFVec4(self.as_raw(), rhs.0) })
     }
 }
 
@@ -186,7 +186,7 @@ impl Mul<FVec3> for &Matrix4 {
 
     #[doc(alias = "Mtx_MultiplyFVecH")]
     fn mul(self, rhs: FVec3) -> Self::Output {
-        FVec(unsafe { citro3d_sys::Mtx_MultiplyFVecH(self.as_raw(), rhs.0) })
+        FVec(unsafe { sys::Mtx_MultiplyFVecH(self.as_raw(), rhs.0) })
     }
 }
 
@@ -16,10 +16,10 @@ pub struct Projection<Kind> {
 }
 
 impl<Kind> Projection<Kind> {
-    fn new(inner: Kind) -> Self {
+    const fn new(inner: Kind) -> Self {
         Self {
-            coordinates: CoordinateOrientation::default(),
-            rotation: ScreenOrientation::default(),
+            coordinates: CoordinateOrientation::RightHanded,
+            rotation: ScreenOrientation::Rotated,
             inner,
         }
     }
@@ -40,7 +40,7 @@ impl<Kind> Projection<Kind> {
     ///     .coordinates(CoordinateOrientation::LeftHanded)
     ///     .into();
     /// ```
-    pub fn coordinates(mut self, orientation: CoordinateOrientation) -> Self {
+    pub const fn coordinates(mut self, orientation: CoordinateOrientation) -> Self {
         self.coordinates = orientation;
         self
     }
@@ -61,7 +61,7 @@ impl<Kind> Projection<Kind> {
     ///     .screen(ScreenOrientation::None)
     ///     .into();
     /// ```
-    pub fn screen(mut self, orientation: ScreenOrientation) -> Self {
+    pub const fn screen(mut self, orientation: ScreenOrientation) -> Self {
         self.rotation = orientation;
         self
     }
@@ -109,7 +109,7 @@ impl Projection<Perspective> {
     /// ```
     #[doc(alias = "Mtx_Persp")]
     #[doc(alias = "Mtx_PerspTilt")]
-    pub fn perspective(
+    pub const fn perspective(
         vertical_fov_radians: f32,
         aspect_ratio: AspectRatio,
         clip_planes: ClipPlanes,
@@ -162,6 +162,84 @@ impl Projection<Perspective> {
         self.inner.stereo = Some(displacement);
         self
     }
+
+    /// Compute the projection matrix in pure Rust, without calling into
+    /// `citro3d`. The result matches what the [`From`] conversion to
+    /// [`Matrix4`] produces, so this can be used to compute projections on the
+    /// host, e.g. in tests without a GPU present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::f32::consts::PI;
+    /// # use citro3d::math::{AspectRatio, ClipPlanes, Matrix4, Projection};
+    /// # use approx::assert_abs_diff_eq;
+    /// #
+    /// let projection = Projection::perspective(
+    ///     PI / 4.0,
+    ///     AspectRatio::TopScreen,
+    ///     ClipPlanes {
+    ///         near: 0.01,
+    ///         far: 100.0,
+    ///     },
+    /// );
+    ///
+    /// let mtx = projection.matrix();
+    /// assert_abs_diff_eq!(mtx, Matrix4::from(projection));
+    /// ```
+    pub fn matrix(&self) -> Matrix4 {
+        let Perspective {
+            vertical_fov_radians,
+            aspect_ratio,
+            clip_planes: ClipPlanes { near, far },
+            stereo,
+        } = self.inner;
+
+        // Ported from citro3d's `mtx_persp*.c`, keeping the same order of
+        // operations so the results are identical.
+        let aspect = f32::from(aspect_ratio);
+        let fov_tan = (vertical_fov_radians / 2.0).tan();
+        let handedness = if self.coordinates.is_left_handed() {
+            1.0
+        } else {
+            -1.0
+        };
+
+        let mut rows = [[0.0; 4]; 4];
+        rows[2][3] = far * near / (near - far);
+        rows[3][2] = handedness;
+        rows[2][2] = -handedness * near / (near - far);
+
+        match (self.rotation, stereo) {
+            (ScreenOrientation::None, None) => {
+                rows[0][0] = 1.0 / (aspect * fov_tan);
+                rows[1][1] = 1.0 / fov_tan;
+            }
+            (ScreenOrientation::Rotated, None) => {
+                rows[0][1] = 1.0 / fov_tan;
+                rows[1][0] = -1.0 / (fov_tan * aspect);
+            }
+            (ScreenOrientation::None, Some(stereo)) => {
+                let fov_tan_aspect = fov_tan * aspect;
+                let shift = stereo.displacement / (2.0 * stereo.screen_depth);
+                rows[0][0] = 1.0 / fov_tan_aspect;
+                rows[0][3] = -stereo.displacement / 2.0;
+                rows[1][1] = 1.0 / fov_tan;
+                rows[0][2] = handedness * shift / fov_tan_aspect;
+            }
+            (ScreenOrientation::Rotated, Some(stereo)) => {
+                let fov_tan_aspect = fov_tan * aspect;
+                let shift = stereo.displacement / (2.0 * stereo.screen_depth);
+                rows[0][1] = 1.0 / fov_tan;
+                rows[1][0] = -1.0 / fov_tan_aspect;
+                rows[1][3] = stereo.displacement / 2.0;
+                rows[1][2] = -handedness * shift / fov_tan_aspect;
+            }
+        }
+
+        Matrix4::from_rows_xyzw(rows)
+    }
 }
 
 impl From<Projection<Perspective>> for Matrix4 {
@@ -247,7 +325,7 @@ impl Projection<Orthographic> {
     /// ```
     #[doc(alias = "Mtx_Ortho")]
     #[doc(alias = "Mtx_OrthoTilt")]
-    pub fn orthographic(
+    pub const fn orthographic(
         clip_planes_x: Range<f32>,
         clip_planes_y: Range<f32>,
         clip_planes_z: ClipPlanes,
@@ -260,6 +338,67 @@ impl Projection<Orthographic> {
     }
 }
 
+impl Projection<Orthographic> {
+    /// Compute the projection matrix in pure Rust, without calling into
+    /// `citro3d`. The result matches what the [`From`] conversion to
+    /// [`Matrix4`] produces, but unlike that conversion this can be
+    /// evaluated in `const` contexts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{ClipPlanes, Matrix4, Projection};
+    /// #
+    /// const PROJECTION: Matrix4 = Projection::orthographic(
+    ///     0.0..240.0,
+    ///     0.0..400.0,
+    ///     ClipPlanes {
+    ///         near: 0.0,
+    ///         far: 100.0,
+    ///     },
+    /// )
+    /// .matrix();
+    /// ```
+    pub const fn matrix(&self) -> Matrix4 {
+        let Orthographic {
+            clip_planes_x,
+            clip_planes_y,
+            clip_planes_z: ClipPlanes { near, far },
+        } = &self.inner;
+        let (near, far) = (*near, *far);
+        let (left, right) = (clip_planes_x.start, clip_planes_x.end);
+        let (bottom, top) = (clip_planes_y.start, clip_planes_y.end);
+
+        // Ported from citro3d's `mtx_ortho*.c`, keeping the same order of
+        // operations so the results are identical.
+        let mut rows = [[0.0; 4]; 4];
+        match self.rotation {
+            ScreenOrientation::None => {
+                rows[0][0] = 2.0 / (right - left);
+                rows[0][3] = (left + right) / (left - right);
+                rows[1][1] = 2.0 / (top - bottom);
+                rows[1][3] = (bottom + top) / (bottom - top);
+            }
+            ScreenOrientation::Rotated => {
+                rows[0][1] = 2.0 / (top - bottom);
+                rows[0][3] = (bottom + top) / (bottom - top);
+                rows[1][0] = 2.0 / (left - right);
+                rows[1][3] = (left + right) / (right - left);
+            }
+        }
+        rows[2][2] = if self.coordinates.is_left_handed() {
+            1.0 / (far - near)
+        } else {
+            1.0 / (near - far)
+        };
+        rows[2][3] = 0.5 * (near + far) / (near - far) - 0.5;
+        rows[3][3] = 1.0;
+
+        Matrix4::from_rows_xyzw(rows)
+    }
+}
+
 impl From<Projection<Orthographic>> for Matrix4 {
     fn from(projection: Projection<Orthographic>) -> Self {
         let make_mtx = match projection.rotation {
@@ -304,7 +443,7 @@ pub enum CoordinateOrientation {
 }
 
 impl CoordinateOrientation {
-    pub(crate) fn is_left_handed(self) -> bool {
+    pub(crate) const fn is_left_handed(self) -> bool {
         matches!(self, Self::LeftHanded)
     }
 }
@@ -373,6 +512,61 @@ impl StereoDisplacement {
 
         (left_eye, right_eye)
     }
+
+    /// Offset an arbitrary view matrix for the left and right eyes, returning
+    /// `(left_eye, right_eye)`. Each eye is moved sideways by the magnitude of
+    /// [`displacement`](Self::displacement), and the views are sheared so that
+    /// objects at [`screen_depth`](Self::screen_depth) line up in both eyes.
+    ///
+    /// Unlike [`Projection::stereo_matrices`], this works with any projection
+    /// matrix (e.g. oblique or off-center ones), since the result only needs to
+    /// be multiplied by the projection as usual. This assumes the default
+    /// [`CoordinateOrientation`]; see [`apply_to_view_with`](Self::apply_to_view_with)
+    /// to use a different one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{Matrix4, StereoDisplacement};
+    /// let (eye, _) = StereoDisplacement::new(0.5, 2.0);
+    /// let view = Matrix4::identity();
+    /// let (left_view, right_view) = eye.apply_to_view(&view);
+    /// ```
+    pub fn apply_to_view(&self, view: &Matrix4) -> (Matrix4, Matrix4) {
+        self.apply_to_view_with(view, CoordinateOrientation::default())
+    }
+
+    /// Like [`apply_to_view`](Self::apply_to_view), but for a view matrix
+    /// using the given [`CoordinateOrientation`].
+    pub fn apply_to_view_with(
+        &self,
+        view: &Matrix4,
+        coordinates: CoordinateOrientation,
+    ) -> (Matrix4, Matrix4) {
+        let offset = self.displacement.abs();
+        // Depth along the view direction is +Z for left-handed coordinates
+        // and -Z for right-handed ones
+        let into_screen = if coordinates.is_left_handed() {
+            1.0
+        } else {
+            -1.0
+        };
+
+        let eye = |offset: f32| {
+            // x' = x - offset + offset * depth / screen_depth, which leaves
+            // points at the screen depth where they were
+            let shear = Matrix4::from_rows_xyzw([
+                [1.0, 0.0, into_screen * offset / self.screen_depth, -offset],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]);
+            shear * *view
+        };
+
+        (eye(-offset), eye(offset))
+    }
 }
 
 /// Configuration for the clipping planes of a projection.
@@ -422,3 +616,78 @@ impl From<AspectRatio> for f32 {
 }
 
 // endregion
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::math::FVec3;
+
+    const CLIP_PLANES: ClipPlanes = ClipPlanes {
+        near: 0.01,
+        far: 100.0,
+    };
+
+    fn assert_matches_ffi<Kind: Clone>(
+        projection: Projection<Kind>,
+        matrix: fn(&Projection<Kind>) -> Matrix4,
+    ) where
+        Matrix4: From<Projection<Kind>>,
+    {
+        for coordinates in [
+            CoordinateOrientation::LeftHanded,
+            CoordinateOrientation::RightHanded,
+        ] {
+            for screen in [ScreenOrientation::Rotated, ScreenOrientation::None] {
+                let projection = projection.clone().coordinates(coordinates).screen(screen);
+                assert_eq!(
+                    matrix(&projection).rows_xyzw(),
+                    Matrix4::from(projection).rows_xyzw(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn stereo_view_converges_at_screen_depth() {
+        let (eye, _) = StereoDisplacement::new(0.5, 2.0);
+
+        for (coordinates, depth) in [
+            (CoordinateOrientation::LeftHanded, 2.0),
+            (CoordinateOrientation::RightHanded, -2.0),
+        ] {
+            let (left, right) = eye.apply_to_view_with(&Matrix4::identity(), coordinates);
+
+            let on_screen = FVec3::new(1.0, 0.0, depth);
+            assert_abs_diff_eq!((&left * on_screen).x(), 1.0);
+            assert_abs_diff_eq!((&right * on_screen).x(), 1.0);
+
+            let at_eye = FVec3::new(1.0, 0.0, 0.0);
+            assert_abs_diff_eq!((&left * at_eye).x(), 1.25);
+            assert_abs_diff_eq!((&right * at_eye).x(), 0.75);
+        }
+    }
+
+    #[test]
+    fn perspective_matches_ffi() {
+        let projection = Projection::perspective(PI / 4.0, AspectRatio::TopScreen, CLIP_PLANES);
+        let (left, right) = StereoDisplacement::new(0.5, 2.0);
+
+        assert_matches_ffi(projection.clone(), Projection::<Perspective>::matrix);
+        assert_matches_ffi(
+            projection.clone().stereo(left),
+            Projection::<Perspective>::matrix,
+        );
+        assert_matches_ffi(projection.stereo(right), Projection::<Perspective>::matrix);
+    }
+
+    #[test]
+    fn orthographic_matches_ffi() {
+        let projection = Projection::orthographic(0.0..240.0, 0.0..400.0, CLIP_PLANES);
+
+        assert_matches_ffi(projection, Projection::<Orthographic>::matrix);
+    }
+}
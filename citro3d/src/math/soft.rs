@@ -0,0 +1,296 @@
+//! Pure-Rust implementations of the `citro3d` math functions used by this
+//! module, enabled by the `pure-rust-math` feature. These mirror the signatures
+//! of their `citro3d_sys` counterparts so they can be swapped in transparently
+//! (see [`super::sys`]), and are ported from citro3d's implementations.
+//!
+//! Only cheap, commonly used operations are implemented here; anything else
+//! (rotations around an arbitrary axis, inverse, etc.) still calls into `citro3d`.
+
+#![allow(non_snake_case)]
+
+use citro3d_sys::{C3D_FVec, C3D_Mtx};
+
+fn xyzw(v: C3D_FVec) -> [f32; 4] {
+    let [w, z, y, x] = unsafe { v.c };
+    [x, y, z, w]
+}
+
+fn rows(mtx: *const C3D_Mtx) -> [[f32; 4]; 4] {
+    unsafe { (*mtx).r }.map(xyzw)
+}
+
+fn new(x: f32, y: f32, z: f32, w: f32) -> C3D_FVec {
+    C3D_FVec { c: [w, z, y, x] }
+}
+
+fn write_rows(out: *mut C3D_Mtx, rows: [[f32; 4]; 4]) {
+    let r = rows.map(|[x, y, z, w]| new(x, y, z, w));
+    unsafe { out.write(C3D_Mtx { r }) };
+}
+
+// region: FVec4
+
+pub(crate) unsafe fn FVec4_New(x: f32, y: f32, z: f32, w: f32) -> C3D_FVec {
+    new(x, y, z, w)
+}
+
+pub(crate) unsafe fn FVec4_Add(lhs: C3D_FVec, rhs: C3D_FVec) -> C3D_FVec {
+    let ([x0, y0, z0, w0], [x1, y1, z1, w1]) = (xyzw(lhs), xyzw(rhs));
+    FVec4_New(x0 + x1, y0 + y1, z0 + z1, w0 + w1)
+}
+
+pub(crate) unsafe fn FVec4_Subtract(lhs: C3D_FVec, rhs: C3D_FVec) -> C3D_FVec {
+    let ([x0, y0, z0, w0], [x1, y1, z1, w1]) = (xyzw(lhs), xyzw(rhs));
+    FVec4_New(x0 - x1, y0 - y1, z0 - z1, w0 - w1)
+}
+
+pub(crate) unsafe fn FVec4_Negate(v: C3D_FVec) -> C3D_FVec {
+    let [x, y, z, w] = xyzw(v);
+    FVec4_New(-x, -y, -z, -w)
+}
+
+pub(crate) unsafe fn FVec4_Scale(v: C3D_FVec, s: f32) -> C3D_FVec {
+    let [x, y, z, w] = xyzw(v);
+    FVec4_New(x * s, y * s, z * s, w * s)
+}
+
+pub(crate) unsafe fn FVec4_PerspDivide(v: C3D_FVec) -> C3D_FVec {
+    let [x, y, z, w] = xyzw(v);
+    FVec4_New(x / w, y / w, z / w, 1.0)
+}
+
+pub(crate) unsafe fn FVec4_Dot(lhs: C3D_FVec, rhs: C3D_FVec) -> f32 {
+    let ([x0, y0, z0, w0], [x1, y1, z1, w1]) = (xyzw(lhs), xyzw(rhs));
+    x0 * x1 + y0 * y1 + z0 * z1 + w0 * w1
+}
+
+pub(crate) unsafe fn FVec4_Magnitude(v: C3D_FVec) -> f32 {
+    FVec4_Dot(v, v).sqrt()
+}
+
+pub(crate) unsafe fn FVec4_Normalize(v: C3D_FVec) -> C3D_FVec {
+    let m = FVec4_Magnitude(v);
+    let [x, y, z, w] = xyzw(v);
+    FVec4_New(x / m, y / m, z / m, w / m)
+}
+
+// endregion
+
+// region: FVec3
+
+pub(crate) unsafe fn FVec3_New(x: f32, y: f32, z: f32) -> C3D_FVec {
+    new(x, y, z, 0.0)
+}
+
+pub(crate) unsafe fn FVec3_Add(lhs: C3D_FVec, rhs: C3D_FVec) -> C3D_FVec {
+    let ([x0, y0, z0, _], [x1, y1, z1, _]) = (xyzw(lhs), xyzw(rhs));
+    FVec3_New(x0 + x1, y0 + y1, z0 + z1)
+}
+
+pub(crate) unsafe fn FVec3_Subtract(lhs: C3D_FVec, rhs: C3D_FVec) -> C3D_FVec {
+    let ([x0, y0, z0, _], [x1, y1, z1, _]) = (xyzw(lhs), xyzw(rhs));
+    FVec3_New(x0 - x1, y0 - y1, z0 - z1)
+}
+
+pub(crate) unsafe fn FVec3_Negate(v: C3D_FVec) -> C3D_FVec {
+    let [x, y, z, _] = xyzw(v);
+    FVec3_New(-x, -y, -z)
+}
+
+pub(crate) unsafe fn FVec3_Scale(v: C3D_FVec, s: f32) -> C3D_FVec {
+    let [x, y, z, _] = xyzw(v);
+    FVec3_New(x * s, y * s, z * s)
+}
+
+pub(crate) unsafe fn FVec3_Dot(lhs: C3D_FVec, rhs: C3D_FVec) -> f32 {
+    let ([x0, y0, z0, _], [x1, y1, z1, _]) = (xyzw(lhs), xyzw(rhs));
+    x0 * x1 + y0 * y1 + z0 * z1
+}
+
+pub(crate) unsafe fn FVec3_Magnitude(v: C3D_FVec) -> f32 {
+    FVec3_Dot(v, v).sqrt()
+}
+
+pub(crate) unsafe fn FVec3_Normalize(v: C3D_FVec) -> C3D_FVec {
+    let m = FVec3_Magnitude(v);
+    let [x, y, z, _] = xyzw(v);
+    FVec3_New(x / m, y / m, z / m)
+}
+
+pub(crate) unsafe fn FVec3_Distance(lhs: C3D_FVec, rhs: C3D_FVec) -> f32 {
+    FVec3_Magnitude(FVec3_Subtract(lhs, rhs))
+}
+
+pub(crate) unsafe fn FVec3_Cross(lhs: C3D_FVec, rhs: C3D_FVec) -> C3D_FVec {
+    let ([x0, y0, z0, _], [x1, y1, z1, _]) = (xyzw(lhs), xyzw(rhs));
+    FVec3_New(y0 * z1 - z0 * y1, z0 * x1 - x0 * z1, x0 * y1 - y0 * x1)
+}
+
+// endregion
+
+// region: Matrix
+
+pub(crate) unsafe fn Mtx_Zeros(out: *mut C3D_Mtx) {
+    out.write(C3D_Mtx { m: [0.0; 16] });
+}
+
+pub(crate) unsafe fn Mtx_Identity(out: *mut C3D_Mtx) {
+    Mtx_Diagonal(out, 1.0, 1.0, 1.0, 1.0);
+}
+
+pub(crate) unsafe fn Mtx_Diagonal(out: *mut C3D_Mtx, x: f32, y: f32, z: f32, w: f32) {
+    write_rows(
+        out,
+        [
+            [x, 0.0, 0.0, 0.0],
+            [0.0, y, 0.0, 0.0],
+            [0.0, 0.0, z, 0.0],
+            [0.0, 0.0, 0.0, w],
+        ],
+    );
+}
+
+pub(crate) unsafe fn Mtx_Transpose(out: *mut C3D_Mtx) {
+    let m = rows(out);
+    write_rows(
+        out,
+        std::array::from_fn(|i| std::array::from_fn(|j| m[j][i])),
+    );
+}
+
+pub(crate) unsafe fn Mtx_Add(out: *mut C3D_Mtx, lhs: *const C3D_Mtx, rhs: *const C3D_Mtx) {
+    let (l, r) = ((*lhs).m, (*rhs).m);
+    out.write(C3D_Mtx {
+        m: std::array::from_fn(|i| l[i] + r[i]),
+    });
+}
+
+pub(crate) unsafe fn Mtx_Subtract(out: *mut C3D_Mtx, lhs: *const C3D_Mtx, rhs: *const C3D_Mtx) {
+    let (l, r) = ((*lhs).m, (*rhs).m);
+    out.write(C3D_Mtx {
+        m: std::array::from_fn(|i| l[i] - r[i]),
+    });
+}
+
+pub(crate) unsafe fn Mtx_Multiply(out: *mut C3D_Mtx, a: *const C3D_Mtx, b: *const C3D_Mtx) {
+    let (a, b) = (rows(a), rows(b));
+    write_rows(
+        out,
+        std::array::from_fn(|j| {
+            std::array::from_fn(|i| {
+                a[j][0] * b[0][i] + a[j][1] * b[1][i] + a[j][2] * b[2][i] + a[j][3] * b[3][i]
+            })
+        }),
+    );
+}
+
+pub(crate) unsafe fn Mtx_MultiplyFVec4(mtx: *const C3D_Mtx, v: C3D_FVec) -> C3D_FVec {
+    let [r0, r1, r2, r3] = (*mtx).r;
+    FVec4_New(
+        FVec4_Dot(r0, v),
+        FVec4_Dot(r1, v),
+        FVec4_Dot(r2, v),
+        FVec4_Dot(r3, v),
+    )
+}
+
+pub(crate) unsafe fn Mtx_MultiplyFVecH(mtx: *const C3D_Mtx, v: C3D_FVec) -> C3D_FVec {
+    let [x, y, z, _] = xyzw(v);
+    Mtx_MultiplyFVec4(mtx, new(x, y, z, 1.0))
+}
+
+pub(crate) unsafe fn Mtx_Translate(mtx: *mut C3D_Mtx, x: f32, y: f32, z: f32, is_right_side: bool) {
+    let mut m = rows(mtx);
+    let t = [x, y, z, 1.0];
+    if is_right_side {
+        // mtx * T: each row gains its dot product with the translation
+        for row in &mut m {
+            row[3] = row[0] * t[0] + row[1] * t[1] + row[2] * t[2] + row[3] * t[3];
+        }
+    } else {
+        // T * mtx: each of the first three rows gains a multiple of the last
+        let last = m[3];
+        for (row, offset) in m.iter_mut().zip(t).take(3) {
+            for (cell, l) in row.iter_mut().zip(last) {
+                *cell += offset * l;
+            }
+        }
+    }
+    write_rows(mtx, m);
+}
+
+pub(crate) unsafe fn Mtx_Scale(mtx: *mut C3D_Mtx, x: f32, y: f32, z: f32) {
+    let mut m = rows(mtx);
+    for row in &mut m {
+        row[0] *= x;
+        row[1] *= y;
+        row[2] *= z;
+    }
+    write_rows(mtx, m);
+}
+
+// endregion
+
+#[cfg(test)]
+mod tests {
+    use std::mem::MaybeUninit;
+
+    use super::*;
+
+    fn sample_matrix(offset: f32) -> C3D_Mtx {
+        C3D_Mtx {
+            m: std::array::from_fn(|i| i as f32 * 0.5 + offset),
+        }
+    }
+
+    fn assert_matrix_eq(soft: impl Fn(*mut C3D_Mtx), ffi: impl Fn(*mut C3D_Mtx)) {
+        let (mut l, mut r) = (sample_matrix(1.0), sample_matrix(1.0));
+        soft(&mut l);
+        ffi(&mut r);
+        assert_eq!(unsafe { l.m }, unsafe { r.m });
+    }
+
+    #[test]
+    fn fvec_ops_match_ffi() {
+        let (a, b) = (new(1.0, -2.0, 3.5, 0.5), new(0.25, 4.0, -1.0, 2.0));
+
+        unsafe {
+            assert_eq!(FVec4_Add(a, b).c, citro3d_sys::FVec4_Add(a, b).c);
+            assert_eq!(FVec4_Dot(a, b), citro3d_sys::FVec4_Dot(a, b));
+            assert_eq!(FVec4_Normalize(a).c, citro3d_sys::FVec4_Normalize(a).c);
+            assert_eq!(FVec3_Cross(a, b).c, citro3d_sys::FVec3_Cross(a, b).c);
+            assert_eq!(FVec3_Distance(a, b), citro3d_sys::FVec3_Distance(a, b));
+        }
+    }
+
+    #[test]
+    fn matrix_ops_match_ffi() {
+        let (a, b) = (sample_matrix(1.0), sample_matrix(-3.0));
+        let v = new(1.0, 2.0, 3.0, 4.0);
+
+        unsafe {
+            let (mut l, mut r) = (MaybeUninit::uninit(), MaybeUninit::uninit());
+            Mtx_Multiply(l.as_mut_ptr(), &a, &b);
+            citro3d_sys::Mtx_Multiply(r.as_mut_ptr(), &a, &b);
+            assert_eq!(l.assume_init().m, r.assume_init().m);
+
+            assert_eq!(
+                Mtx_MultiplyFVecH(&a, v).c,
+                citro3d_sys::Mtx_MultiplyFVecH(&a, v).c
+            );
+        }
+
+        assert_matrix_eq(
+            |m| unsafe { Mtx_Transpose(m) },
+            |m| unsafe { citro3d_sys::Mtx_Transpose(m) },
+        );
+        assert_matrix_eq(
+            |m| unsafe { Mtx_Translate(m, 1.0, 2.0, 3.0, false) },
+            |m| unsafe { citro3d_sys::Mtx_Translate(m, 1.0, 2.0, 3.0, false) },
+        );
+        assert_matrix_eq(
+            |m| unsafe { Mtx_Scale(m, 1.0, 2.0, 3.0) },
+            |m| unsafe { citro3d_sys::Mtx_Scale(m, 1.0, 2.0, 3.0) },
+        );
+    }
+}
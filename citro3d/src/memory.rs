@@ -0,0 +1,91 @@
+//! Memory usage reporting, e.g. for displaying a memory HUD or detecting
+//! leaks during development.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The total amount of VRAM available to applications, in bytes.
+const VRAM_SIZE: usize = 0x60_0000;
+
+static LIVE_BUFFERS: AtomicUsize = AtomicUsize::new(0);
+static LIVE_ARENAS: AtomicUsize = AtomicUsize::new(0);
+
+/// A snapshot of GPU-related memory usage. See [`usage`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Usage {
+    /// Free space in the LINEAR heap, in bytes.
+    pub linear_free: usize,
+    /// Free space in VRAM, in bytes.
+    pub vram_free: usize,
+    /// Used space in VRAM, in bytes.
+    pub vram_used: usize,
+    /// The number of live [`Buffer`](crate::buffer::Buffer)s.
+    pub live_buffers: usize,
+    /// The number of live [`FrameArena`](crate::arena::FrameArena)s.
+    pub live_arenas: usize,
+}
+
+/// Get the current memory usage. The free space is reported by `libctru`'s
+/// allocators, while the live object counts are kept by this crate's wrappers.
+///
+/// Note that the free space is not necessarily contiguous, so it may not be
+/// possible to make a single allocation of that size.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::buffer::Buffer;
+/// let before = citro3d::memory::usage();
+/// let vbo = Buffer::from_slice(&[0_u32; 16]).unwrap();
+/// let after = citro3d::memory::usage();
+///
+/// assert_eq!(after.live_buffers, before.live_buffers + 1);
+/// assert!(after.linear_free < before.linear_free);
+/// ```
+#[doc(alias = "linearSpaceFree")]
+#[doc(alias = "vramSpaceFree")]
+pub fn usage() -> Usage {
+    let linear_free = unsafe { ctru_sys::linearSpaceFree() } as usize;
+    let vram_free = unsafe { ctru_sys::vramSpaceFree() } as usize;
+
+    Usage {
+        linear_free,
+        vram_free,
+        vram_used: VRAM_SIZE.saturating_sub(vram_free),
+        live_buffers: LIVE_BUFFERS.load(Ordering::Relaxed),
+        live_arenas: LIVE_ARENAS.load(Ordering::Relaxed),
+    }
+}
+
+/// Counts a live object for as long as it exists. Embed this in a type to
+/// have it included in [`usage`].
+#[derive(Debug)]
+pub(crate) struct LiveCount(&'static AtomicUsize);
+
+impl LiveCount {
+    pub fn buffer() -> Self {
+        Self::new(&LIVE_BUFFERS)
+    }
+
+    pub fn arena() -> Self {
+        Self::new(&LIVE_ARENAS)
+    }
+
+    fn new(counter: &'static AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Clone for LiveCount {
+    fn clone(&self) -> Self {
+        Self::new(self.0)
+    }
+}
+
+impl Drop for LiveCount {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
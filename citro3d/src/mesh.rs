@@ -0,0 +1,316 @@
+//! Meshes, which bundle together everything needed to draw a piece of
+//! geometry: the vertex data, an optional index buffer, the attribute layout of
+//! the vertices, and the type of primitive they describe.
+
+use std::collections::HashMap;
+
+use crate::attrib::{self, VertexAttributes};
+use crate::buffer::{self, Buffer, IndexType, Primitive};
+
+/// Geometry that can be drawn with a single call to
+/// [`Instance::draw_mesh`](crate::Instance::draw_mesh).
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::buffer::{Buffer, Primitive};
+/// # use citro3d::macros::VertexAttributes;
+/// # use citro3d::mesh::Mesh;
+/// #[repr(C)]
+/// #[derive(Clone, Copy, VertexAttributes)]
+/// struct Vertex {
+///     position: [f32; 3],
+/// }
+///
+/// let vertices = Buffer::from_slice(&[
+///     Vertex {
+///         position: [0.0, 0.0, 0.0],
+///     },
+///     Vertex {
+///         position: [1.0, 0.0, 0.0],
+///     },
+///     Vertex {
+///         position: [1.0, 1.0, 0.0],
+///     },
+///     Vertex {
+///         position: [0.0, 1.0, 0.0],
+///     },
+/// ])
+/// .unwrap();
+/// let indices = Buffer::from_slice(&[0_u16, 1, 2, 2, 3, 0]).unwrap();
+///
+/// let quad = Mesh::new(vertices, Primitive::Triangles)
+///     .unwrap()
+///     .with_indices(indices)
+///     .unwrap();
+/// assert_eq!(quad.element_count(), 6);
+/// ```
+#[derive(Debug)]
+pub struct Mesh<V, I = u16> {
+    vertices: Buffer<V>,
+    indices: Option<Buffer<I>>,
+    attrib_info: attrib::Info,
+    primitive: Primitive,
+}
+
+impl<V: VertexAttributes> Mesh<V> {
+    /// Create a mesh from the given vertices, using the attribute layout
+    /// described by `V`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the attribute info for `V` could not be built.
+    pub fn new(vertices: Buffer<V>, primitive: Primitive) -> crate::Result<Self> {
+        Ok(Self::with_attrib_info(
+            vertices,
+            V::attrib_info()?,
+            primitive,
+        ))
+    }
+}
+
+impl<V> Mesh<V> {
+    /// Create a mesh from the given vertices, with a custom attribute layout.
+    pub fn with_attrib_info(
+        vertices: Buffer<V>,
+        attrib_info: attrib::Info,
+        primitive: Primitive,
+    ) -> Self {
+        Self {
+            vertices,
+            indices: None,
+            attrib_info,
+            primitive,
+        }
+    }
+}
+
+impl<V, I> Mesh<V, I> {
+    /// Use an index buffer to select which vertices make up each primitive.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if any of the
+    /// indices are out of bounds for the mesh's vertices.
+    pub fn with_indices<J: IndexType + Into<usize>>(
+        self,
+        indices: Buffer<J>,
+    ) -> crate::Result<Mesh<V, J>> {
+        if indices.iter().any(|&i| i.into() >= self.vertices.len()) {
+            return Err(crate::Error::InvalidSize);
+        }
+
+        Ok(Mesh {
+            vertices: self.vertices,
+            indices: Some(indices),
+            attrib_info: self.attrib_info,
+            primitive: self.primitive,
+        })
+    }
+
+    /// Get the mesh's vertices.
+    pub fn vertices(&self) -> &[V] {
+        &self.vertices
+    }
+
+    /// Get mutable access to the mesh's vertices. Call [`Buffer::flush`] on
+    /// [`vertex_buffer`](Self::vertex_buffer) afterwards, or use
+    /// [`update_vertices`](Self::update_vertices) instead, so the GPU sees
+    /// the changes.
+    pub fn vertices_mut(&mut self) -> &mut [V] {
+        &mut self.vertices
+    }
+
+    /// Get the buffer holding the mesh's vertices.
+    pub fn vertex_buffer(&self) -> &Buffer<V> {
+        &self.vertices
+    }
+
+    /// Get the mesh's indices, if it has any.
+    pub fn indices(&self) -> Option<&[I]> {
+        self.indices.as_deref()
+    }
+
+    /// Get the attribute layout of the mesh's vertices.
+    pub fn attrib_info(&self) -> &attrib::Info {
+        &self.attrib_info
+    }
+
+    /// Get the type of primitive the mesh is made of.
+    pub fn primitive(&self) -> Primitive {
+        self.primitive
+    }
+
+    /// The number of vertices that will be drawn, i.e. the number of indices
+    /// if the mesh has any, otherwise the number of vertices.
+    pub fn element_count(&self) -> usize {
+        self.indices
+            .as_ref()
+            .map_or(self.vertices.len(), |indices| indices.len())
+    }
+
+    pub(crate) fn index_buffer(&self) -> Option<&Buffer<I>> {
+        self.indices.as_ref()
+    }
+
+    /// Register the mesh's vertices with `buf_info`.
+    pub(crate) fn register<'a>(
+        &'a self,
+        buf_info: &'a mut buffer::Info,
+    ) -> crate::Result<buffer::Slice<'a>> {
+        buf_info.add_buffer(&self.vertices, &self.attrib_info)
+    }
+}
+
+impl<V: Copy, I> Mesh<V, I> {
+    /// Overwrite vertices starting at `offset`. See [`Buffer::update`].
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`Buffer::update`].
+    pub fn update_vertices(&mut self, offset: usize, data: &[V]) -> crate::Result<()> {
+        self.vertices.update(offset, data)
+    }
+}
+
+impl<V: VertexAttributes + Copy> Mesh<V> {
+    /// Build meshes of [`Primitive::Triangles`] from vertices with 32-bit
+    /// indices, as commonly exported by modelling tools.
+    ///
+    /// The GPU only supports 16-bit indices, so the triangles are split into
+    /// as many meshes as needed for each one to use at most 65536 vertices.
+    /// Vertices shared between meshes are duplicated, and the indices are
+    /// rebased for each mesh.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`](crate::Error::InvalidSize) if the number of
+    ///   indices isn't a multiple of 3, or any index is out of bounds
+    /// * Any error from allocating the buffers or building the attribute info
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::macros::VertexAttributes;
+    /// # use citro3d::mesh::Mesh;
+    /// #[repr(C)]
+    /// #[derive(Clone, Copy, VertexAttributes)]
+    /// struct Vertex {
+    ///     position: [f32; 3],
+    /// }
+    ///
+    /// let vertices = [Vertex { position: [0.0; 3] }; 3];
+    /// let meshes = Mesh::from_u32_triangles(&vertices, &[0, 1, 2]).unwrap();
+    /// assert_eq!(meshes.len(), 1);
+    /// ```
+    pub fn from_u32_triangles(vertices: &[V], indices: &[u32]) -> crate::Result<Vec<Self>> {
+        split_triangles(indices, vertices.len(), BATCH_VERTICES)?
+            .into_iter()
+            .map(|batch| {
+                let batch_vertices = Buffer::from_slice(
+                    &batch
+                        .vertices
+                        .iter()
+                        .map(|&i| vertices[i])
+                        .collect::<Vec<_>>(),
+                )?;
+                Self::new(batch_vertices, Primitive::Triangles)?
+                    .with_indices(Buffer::from_slice(&batch.indices)?)
+            })
+            .collect()
+    }
+}
+
+/// The most vertices addressable by a single 16-bit index buffer.
+const BATCH_VERTICES: usize = u16::MAX as usize + 1;
+
+/// A group of triangles which only use up to a limited number of vertices.
+#[derive(Debug, Default, PartialEq)]
+struct Batch {
+    /// The original index of each vertex in the batch.
+    vertices: Vec<usize>,
+    /// Indices into `vertices` for each triangle corner.
+    indices: Vec<u16>,
+}
+
+/// Split a list of triangles using 32-bit indices into batches which each use
+/// at most `max_vertices` vertices.
+fn split_triangles(
+    indices: &[u32],
+    vertex_count: usize,
+    max_vertices: usize,
+) -> crate::Result<Vec<Batch>> {
+    if indices.len() % 3 != 0 || max_vertices < 3 {
+        return Err(crate::Error::InvalidSize);
+    }
+
+    let mut batches = Vec::new();
+    let mut batch = Batch::default();
+    let mut remap = HashMap::new();
+
+    for triangle in indices.chunks_exact(3) {
+        let mut corners = [0; 3];
+        for (corner, &index) in corners.iter_mut().zip(triangle) {
+            *corner = usize::try_from(index)?;
+            if *corner >= vertex_count {
+                return Err(crate::Error::InvalidSize);
+            }
+        }
+
+        let new_vertices = corners
+            .iter()
+            .enumerate()
+            .filter(|&(i, c)| !remap.contains_key(c) && !corners[..i].contains(c))
+            .count();
+        if batch.vertices.len() + new_vertices > max_vertices {
+            batches.push(std::mem::take(&mut batch));
+            remap.clear();
+        }
+
+        for corner in corners {
+            let index = *remap.entry(corner).or_insert_with(|| {
+                batch.vertices.push(corner);
+                batch.vertices.len() - 1
+            });
+            batch.indices.push(u16::try_from(index)?);
+        }
+    }
+
+    if !batch.indices.is_empty() {
+        batches.push(batch);
+    }
+
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_triangles_rebases_indices() {
+        let batches = split_triangles(&[0, 1, 2, 2, 1, 3, 4, 5, 0], 6, 4).unwrap();
+
+        assert_eq!(
+            batches,
+            [
+                Batch {
+                    vertices: vec![0, 1, 2, 3],
+                    indices: vec![0, 1, 2, 2, 1, 3],
+                },
+                Batch {
+                    vertices: vec![4, 5, 0],
+                    indices: vec![0, 1, 2],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn split_triangles_rejects_bad_indices() {
+        assert!(split_triangles(&[0, 1], 2, 4).is_err());
+        assert!(split_triangles(&[0, 1, 2], 2, 4).is_err());
+    }
+}
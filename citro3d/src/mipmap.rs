@@ -0,0 +1,125 @@
+//! CPU generation of mipmap levels, for textures the GPU can't generate
+//! mipmaps for itself (e.g. those in VRAM, or in formats unsupported by the
+//! display transfer engine).
+//!
+//! Like [`swizzle`](crate::swizzle), this operates on linear, top-to-bottom
+//! images, so each level should be generated before it is tiled.
+
+/// The smallest width or height of a texture (and so of a mipmap level), in
+/// pixels.
+const MIN_SIZE: usize = 8;
+
+/// Halve the size of a linear image with a 2x2 box filter, where each pixel is
+/// made up of `bytes_per_pixel` 8-bit channels (e.g. 4 for RGBA8, 1 for A8).
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if the image is
+/// smaller than 16x16, `width` or `height` is odd, or `src` is not exactly
+/// `width * height * bytes_per_pixel` bytes long.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::mipmap;
+/// let pixels = [0x10, 0x20, 0x30, 0xff].repeat(16 * 16);
+/// let half = mipmap::downsample(&pixels, 16, 16, 4).unwrap();
+/// assert_eq!(half, [0x10, 0x20, 0x30, 0xff].repeat(8 * 8));
+/// ```
+pub fn downsample(
+    src: &[u8],
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+) -> crate::Result<Vec<u8>> {
+    let size = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(bytes_per_pixel))
+        .ok_or(crate::Error::InvalidSize)?;
+
+    if bytes_per_pixel == 0
+        || width < 2 * MIN_SIZE
+        || height < 2 * MIN_SIZE
+        || width % 2 != 0
+        || height % 2 != 0
+        || src.len() != size
+    {
+        return Err(crate::Error::InvalidSize);
+    }
+
+    let stride = width * bytes_per_pixel;
+    let mut dst = Vec::with_capacity(size / 4);
+
+    for rows in src.chunks_exact(2 * stride) {
+        let (top, bottom) = rows.split_at(stride);
+        for x in (0..stride).step_by(2 * bytes_per_pixel) {
+            for channel in x..x + bytes_per_pixel {
+                let right = channel + bytes_per_pixel;
+                let sum = u16::from(top[channel])
+                    + u16::from(top[right])
+                    + u16::from(bottom[channel])
+                    + u16::from(bottom[right]);
+                // Round to nearest
+                dst.push(((sum + 2) / 4) as u8);
+            }
+        }
+    }
+
+    Ok(dst)
+}
+
+/// Generate `levels` successively smaller mipmap levels from a linear image,
+/// not including the full-size image itself. See [`downsample`] for the
+/// filtering used.
+///
+/// # Errors
+///
+/// Fails under the same conditions as [`downsample`], for any of the levels.
+/// In particular, each level can be no smaller than 8x8.
+pub fn generate(
+    src: &[u8],
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+    levels: usize,
+) -> crate::Result<Vec<Vec<u8>>> {
+    let mut chain: Vec<Vec<u8>> = Vec::with_capacity(levels);
+    let (mut width, mut height) = (width, height);
+
+    for _ in 0..levels {
+        let prev = chain.last().map_or(src, Vec::as_slice);
+        let level = downsample(prev, width, height, bytes_per_pixel)?;
+        chain.push(level);
+        width /= 2;
+        height /= 2;
+    }
+
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_each_channel() {
+        // 16x16 image with alternating black and white columns
+        let src: Vec<u8> = (0..16 * 16)
+            .map(|i| if i % 2 == 0 { 0 } else { 255 })
+            .collect();
+        let half = downsample(&src, 16, 16, 1).unwrap();
+        assert_eq!(half.len(), 64);
+        assert!(half.iter().all(|&p| p == 128));
+    }
+
+    #[test]
+    fn chain_stops_at_minimum_size() {
+        let src = vec![0; 32 * 64 * 2];
+        let chain = generate(&src, 32, 64, 2, 2).unwrap();
+        assert_eq!(chain[0].len(), 16 * 32 * 2);
+        assert_eq!(chain[1].len(), 8 * 16 * 2);
+
+        assert!(generate(&src, 32, 64, 2, 3).is_err());
+    }
+}
@@ -0,0 +1,20 @@
+//! Common types and traits for rendering with `citro3d`, to be glob-imported:
+//!
+//! ```
+//! # let _runner = test_runner::GdbRunner::default();
+//! use citro3d::prelude::*;
+//!
+//! let mut instance = Instance::new().unwrap();
+//! ```
+
+pub use crate::attrib::VertexAttributes;
+pub use crate::buffer::{Buffer, Primitive};
+pub use crate::color::Color;
+pub use crate::macros::{include_shader, shader_uniforms, VertexAttributes};
+pub use crate::math::{
+    AspectRatio, ClipPlanes, CoordinateOrientation, FVec3, FVec4, Matrix4, Projection,
+    StereoDisplacement,
+};
+pub use crate::mesh::Mesh;
+pub use crate::render::{ClearFlags, ClearSpec, DepthRange, FrameConfig, ScreenTargets, Target};
+pub use crate::{shader, texenv, Instance};
@@ -0,0 +1,331 @@
+//! Procedural texture support. The PICA200's procedural texture unit generates
+//! texels on the fly from the texture coordinates, using noise and a series of
+//! lookup tables, so gradients and noise effects can be drawn without using
+//! any texture memory.
+//!
+//! A [`ProcTex`] is bound with [`Instance::bind_proctex`](crate::Instance::bind_proctex),
+//! after which its output can be used in texture combiners as
+//! [`Source::Texture3`](crate::texenv::Source::Texture3).
+//!
+//! See also [`proctex.h` documentation](https://oreo639.github.io/citro3d/proctex_8h.html).
+
+use std::fmt;
+use std::mem::MaybeUninit;
+
+use bitflags::bitflags;
+
+use crate::color::Color;
+
+/// The number of entries in a [`ColorLut`].
+pub const COLOR_LUT_SIZE: usize = 256;
+
+/// The configuration of the procedural texture unit.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::color::Color;
+/// # use citro3d::proctex::{Clamp, ColorLut, Lut, MapFunc, ProcTex};
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// // A horizontal gradient from red to blue
+/// let colors = ColorLut::new(&[Color::rgb(0xFF, 0, 0), Color::rgb(0, 0, 0xFF)]).unwrap();
+///
+/// let mut proctex = ProcTex::new(colors);
+/// proctex
+///     .clamp(Clamp::MirroredRepeat, Clamp::MirroredRepeat)
+///     .combiner(MapFunc::U, None)
+///     .rgb_map(Lut::from_fn(|x| x));
+///
+/// instance.bind_proctex(0, &proctex).unwrap();
+/// ```
+#[doc(alias = "C3D_ProcTex")]
+#[derive(Clone)]
+pub struct ProcTex {
+    pub(crate) raw: citro3d_sys::C3D_ProcTex,
+    pub(crate) colors: Box<ColorLut>,
+    pub(crate) noise: Option<Lut>,
+    pub(crate) rgb_map: Option<Lut>,
+    pub(crate) alpha_map: Option<Lut>,
+}
+
+impl fmt::Debug for ProcTex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProcTex").finish_non_exhaustive()
+    }
+}
+
+impl ProcTex {
+    /// Create a procedural texture which maps its combined coordinates onto
+    /// every entry of `colors`, with noise disabled.
+    #[doc(alias = "C3D_ProcTexInit")]
+    pub fn new(colors: ColorLut) -> Self {
+        let mut raw = MaybeUninit::uninit();
+        // SAFETY: C3D_ProcTexInit initializes every field of the struct, and
+        // the color LUT length is at most COLOR_LUT_SIZE
+        let raw = unsafe {
+            citro3d_sys::C3D_ProcTexInit(raw.as_mut_ptr(), 0, colors.len as _);
+            raw.assume_init()
+        };
+
+        Self {
+            raw,
+            colors: Box::new(colors),
+            noise: None,
+            rgb_map: None,
+            alpha_map: None,
+        }
+    }
+
+    /// Enable noise on the given `axes`, which perturbs the texture
+    /// coordinates before they are combined. The shape of the noise is
+    /// determined by the [noise LUT](Self::noise_lut), which must also be set.
+    #[doc(alias = "C3D_ProcTexNoiseCoefs")]
+    pub fn noise(&mut self, axes: Axes, amplitude: f32, frequency: f32, phase: f32) -> &mut Self {
+        unsafe {
+            citro3d_sys::C3D_ProcTexNoiseCoefs(
+                &mut self.raw,
+                axes.bits() as _,
+                amplitude,
+                frequency,
+                phase,
+            );
+        }
+        self
+    }
+
+    /// Enable or disable noise, keeping the coefficients set by
+    /// [`noise`](Self::noise).
+    #[doc(alias = "C3D_ProcTexNoiseEnable")]
+    pub fn noise_enabled(&mut self, enabled: bool) -> &mut Self {
+        unsafe { citro3d_sys::C3D_ProcTexNoiseEnable(&mut self.raw, enabled) };
+        self
+    }
+
+    /// Set how the U and V coordinates are wrapped outside of `0.0..=1.0`.
+    #[doc(alias = "C3D_ProcTexClamp")]
+    pub fn clamp(&mut self, u: Clamp, v: Clamp) -> &mut Self {
+        unsafe { citro3d_sys::C3D_ProcTexClamp(&mut self.raw, u as _, v as _) };
+        self
+    }
+
+    /// Set how the U and V coordinates are shifted on odd or even rows and
+    /// columns, e.g. to make a brick pattern.
+    #[doc(alias = "C3D_ProcTexShift")]
+    pub fn shift(&mut self, u: Shift, v: Shift) -> &mut Self {
+        unsafe { citro3d_sys::C3D_ProcTexShift(&mut self.raw, u as _, v as _) };
+        self
+    }
+
+    /// Set how the U and V coordinates are combined into the single value used
+    /// to look up the output color. If `alpha` is `None`, the alpha channel
+    /// uses the same function as the RGB channels.
+    #[doc(alias = "C3D_ProcTexCombiner")]
+    pub fn combiner(&mut self, rgb: MapFunc, alpha: Option<MapFunc>) -> &mut Self {
+        unsafe {
+            citro3d_sys::C3D_ProcTexCombiner(
+                &mut self.raw,
+                alpha.is_some(),
+                rgb as _,
+                alpha.unwrap_or(rgb) as _,
+            );
+        }
+        self
+    }
+
+    /// Set the filter used when sampling the [`ColorLut`].
+    #[doc(alias = "C3D_ProcTexFilter")]
+    pub fn filter(&mut self, filter: Filter) -> &mut Self {
+        unsafe { citro3d_sys::C3D_ProcTexFilter(&mut self.raw, filter as _) };
+        self
+    }
+
+    /// Set the level of detail bias when sampling the [`ColorLut`].
+    #[doc(alias = "C3D_ProcTexLodBias")]
+    pub fn lod_bias(&mut self, bias: f32) -> &mut Self {
+        unsafe { citro3d_sys::C3D_ProcTexLodBias(&mut self.raw, bias) };
+        self
+    }
+
+    /// Set the lookup table defining the shape of the noise.
+    pub fn noise_lut(&mut self, lut: Lut) -> &mut Self {
+        self.noise = Some(lut);
+        self
+    }
+
+    /// Set the lookup table applied to the combined coordinate for the RGB
+    /// channels, before looking up the output color.
+    pub fn rgb_map(&mut self, lut: Lut) -> &mut Self {
+        self.rgb_map = Some(lut);
+        self
+    }
+
+    /// Set the lookup table applied to the combined coordinate for the alpha
+    /// channel, when a separate alpha [combiner](Self::combiner) is used.
+    pub fn alpha_map(&mut self, lut: Lut) -> &mut Self {
+        self.alpha_map = Some(lut);
+        self
+    }
+}
+
+/// A lookup table mapping values from `0.0` to `1.0` onto `0.0` to `1.0`,
+/// used for noise and to remap the combined texture coordinates.
+#[doc(alias = "C3D_ProcTexLut")]
+#[derive(Clone, Copy)]
+pub struct Lut(pub(crate) citro3d_sys::C3D_ProcTexLut);
+
+impl fmt::Debug for Lut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lut").finish_non_exhaustive()
+    }
+}
+
+impl Lut {
+    /// Create a lookup table by sampling `f` at 129 evenly spaced points from
+    /// `0.0` to `1.0` (inclusive).
+    #[doc(alias = "ProcTexLut_FromArray")]
+    pub fn from_fn(mut f: impl FnMut(f32) -> f32) -> Self {
+        let values: [f32; 129] = std::array::from_fn(|i| f(i as f32 / 128.0));
+
+        let mut lut = MaybeUninit::uninit();
+        // SAFETY: ProcTexLut_FromArray reads exactly 129 values and writes
+        // every entry of the table
+        unsafe {
+            citro3d_sys::ProcTexLut_FromArray(lut.as_mut_ptr(), values.as_ptr());
+            Self(lut.assume_init())
+        }
+    }
+}
+
+/// The table of colors a [`ProcTex`] looks its output up in.
+#[doc(alias = "C3D_ProcTexColorLut")]
+#[derive(Clone)]
+pub struct ColorLut {
+    pub(crate) raw: citro3d_sys::C3D_ProcTexColorLut,
+    len: usize,
+}
+
+impl fmt::Debug for ColorLut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ColorLut")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ColorLut {
+    /// Create a color table from a list of colors. The colors are spread
+    /// evenly over the range of the combined texture coordinates.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if `colors`
+    /// is empty or has more than [`COLOR_LUT_SIZE`] entries.
+    #[doc(alias = "ProcTexColorLut_Write")]
+    pub fn new(colors: &[Color]) -> crate::Result<Self> {
+        if colors.is_empty() || colors.len() > COLOR_LUT_SIZE {
+            return Err(crate::Error::InvalidSize);
+        }
+
+        let colors: Vec<u32> = colors.iter().map(|color| color.to_gpu()).collect();
+
+        // SAFETY: the table is plain data, so all zeroes is a valid value
+        let mut raw: citro3d_sys::C3D_ProcTexColorLut = unsafe { std::mem::zeroed() };
+        unsafe {
+            citro3d_sys::ProcTexColorLut_Write(&mut raw, colors.as_ptr(), 0, colors.len() as _);
+        }
+
+        Ok(Self {
+            raw,
+            len: colors.len(),
+        })
+    }
+
+    /// The number of colors in the table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always `false`, since a color table can't be empty.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+bitflags! {
+    /// The texture coordinates to apply noise to.
+    pub struct Axes: u32 {
+        #[allow(missing_docs)]
+        const U = citro3d_sys::C3D_ProcTex_U;
+        #[allow(missing_docs)]
+        const V = citro3d_sys::C3D_ProcTex_V;
+        #[allow(missing_docs)]
+        const UV = citro3d_sys::C3D_ProcTex_UV;
+    }
+}
+
+/// How texture coordinates are wrapped outside of `0.0..=1.0`.
+#[doc(alias = "GPU_PROCTEX_CLAMP")]
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Clamp {
+    ToZero = ctru_sys::GPU_PT_CLAMP_TO_ZERO,
+    ToEdge = ctru_sys::GPU_PT_CLAMP_TO_EDGE,
+    Repeat = ctru_sys::GPU_PT_REPEAT,
+    MirroredRepeat = ctru_sys::GPU_PT_MIRRORED_REPEAT,
+    Pulse = ctru_sys::GPU_PT_PULSE,
+}
+
+/// How texture coordinates are shifted on alternating rows or columns.
+#[doc(alias = "GPU_PROCTEX_SHIFT")]
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Shift {
+    None = ctru_sys::GPU_PT_NONE,
+    Odd = ctru_sys::GPU_PT_ODD,
+    Even = ctru_sys::GPU_PT_EVEN,
+}
+
+/// A function combining the U and V coordinates into a single value.
+#[doc(alias = "GPU_PROCTEX_MAPFUNC")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MapFunc {
+    /// `u`
+    U = ctru_sys::GPU_PT_U,
+    /// `u²`
+    U2 = ctru_sys::GPU_PT_U2,
+    /// `v`
+    V = ctru_sys::GPU_PT_V,
+    /// `v²`
+    V2 = ctru_sys::GPU_PT_V2,
+    /// `(u + v) / 2`
+    Add = ctru_sys::GPU_PT_ADD,
+    /// `(u² + v²) / 2`
+    Add2 = ctru_sys::GPU_PT_ADD2,
+    /// `√(u² + v²)`
+    Sqrt2 = ctru_sys::GPU_PT_SQRT2,
+    /// `min(u, v)`
+    Min = ctru_sys::GPU_PT_MIN,
+    /// `max(u, v)`
+    Max = ctru_sys::GPU_PT_MAX,
+    /// A rounded maximum of `u` and `v`, between [`Max`](Self::Max) and
+    /// [`Sqrt2`](Self::Sqrt2)
+    RMax = ctru_sys::GPU_PT_RMAX,
+}
+
+/// The filter used when sampling a [`ColorLut`].
+#[doc(alias = "GPU_PROCTEX_FILTER")]
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Filter {
+    Nearest = ctru_sys::GPU_PT_NEAREST,
+    Linear = ctru_sys::GPU_PT_LINEAR,
+    NearestMipNearest = ctru_sys::GPU_PT_NEAREST_MIP_NEAREST,
+    LinearMipNearest = ctru_sys::GPU_PT_LINEAR_MIP_NEAREST,
+    NearestMipLinear = ctru_sys::GPU_PT_NEAREST_MIP_LINEAR,
+    LinearMipLinear = ctru_sys::GPU_PT_LINEAR_MIP_LINEAR,
+}
@@ -1,7 +1,7 @@
 //! This module provides render target types and options for controlling transfer
 //! of data to the GPU, including the format of color and depth data to be rendered.
 
-use std::cell::RefMut;
+use std::cell::{Cell, RefMut};
 use std::rc::Rc;
 
 use citro3d_sys::{
@@ -11,10 +11,27 @@ use ctru::services::gfx::Screen;
 use ctru::services::gspgpu::FramebufferFormat;
 use ctru_sys::{GPU_COLORBUF, GPU_DEPTHBUF};
 
+use crate::color::Color;
 use crate::{Error, RenderQueue, Result};
 
 mod transfer;
 
+/// The `(width, height)` of a render target covering the top screen, as passed
+/// to [`Instance::render_target`](crate::Instance::render_target).
+///
+/// The 3DS screens are mounted rotated 90 degrees, so framebuffers (and thus
+/// render targets) are taller than they are wide: the 400x240 top screen takes
+/// a target 240 pixels wide and 400 pixels tall.
+pub const TOP_SCREEN_SIZE: (usize, usize) = (240, 400);
+
+/// The `(width, height)` of a render target covering the top screen in wide
+/// (800px) mode. See [`TOP_SCREEN_SIZE`] for why the dimensions are swapped.
+pub const TOP_WIDE_SIZE: (usize, usize) = (240, 800);
+
+/// The `(width, height)` of a render target covering the bottom screen. See
+/// [`TOP_SCREEN_SIZE`] for why the dimensions are swapped.
+pub const BOTTOM_SCREEN_SIZE: (usize, usize) = (240, 320);
+
 /// A render target for `citro3d`. Frame data will be written to this target
 /// to be rendered on the GPU and displayed on the screen.
 #[doc(alias = "C3D_RenderTarget")]
@@ -24,6 +41,9 @@ pub struct Target<'screen> {
     // screen this target writes to during rendering
     _screen: RefMut<'screen, dyn Screen>,
     _queue: Rc<RenderQueue>,
+    default_clear: Option<ClearSpec>,
+    // The frame in which the default clear was last applied
+    cleared_frame: Cell<Option<u64>>,
 }
 
 impl Drop for Target<'_> {
@@ -39,6 +59,10 @@ impl<'screen> Target<'screen> {
     /// Create a new render target with the given parameters. This takes a
     /// [`RenderQueue`] parameter to make sure this  [`Target`] doesn't outlive
     /// the render queue.
+    ///
+    /// Fails with [`Error::InvalidSize`] if the target is larger than the
+    /// screen's framebuffer in either dimension, which usually means the
+    /// width and height were swapped.
     pub(crate) fn new(
         width: usize,
         height: usize,
@@ -46,6 +70,11 @@ impl<'screen> Target<'screen> {
         depth_format: Option<DepthFormat>,
         queue: Rc<RenderQueue>,
     ) -> Result<Self> {
+        let framebuffer = screen.raw_framebuffer();
+        if width == 0 || height == 0 || width > framebuffer.width || height > framebuffer.height {
+            return Err(Error::InvalidSize);
+        }
+
         let color_format: ColorFormat = screen.framebuffer_format().into();
 
         let raw = unsafe {
@@ -79,6 +108,8 @@ impl<'screen> Target<'screen> {
             raw,
             _screen: screen,
             _queue: queue,
+            default_clear: None,
+            cleared_frame: Cell::new(None),
         })
     }
 
@@ -91,12 +122,93 @@ impl<'screen> Target<'screen> {
         }
     }
 
+    /// Clear the render target as described by `spec`.
+    #[doc(alias = "C3D_RenderTargetClear")]
+    pub fn clear_with(&mut self, spec: ClearSpec) {
+        self.clear(spec.flags, spec.color, spec.depth);
+    }
+
+    /// Set a clear to apply automatically the first time this target is
+    /// [selected](crate::Instance::select_render_target) in each frame, so it
+    /// doesn't need to be cleared manually before drawing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::Gfx;
+    /// # use citro3d::render::{ClearFlags, ClearSpec};
+    /// #
+    /// let gfx = Gfx::new().unwrap();
+    /// let mut instance = citro3d::Instance::new().unwrap();
+    ///
+    /// let (width, height) = citro3d::render::TOP_SCREEN_SIZE;
+    /// let target = instance
+    ///     .render_target(width, height, gfx.top_screen.borrow_mut(), None)
+    ///     .unwrap()
+    ///     .with_default_clear(ClearSpec::new(ClearFlags::ALL, 0x68_B0_D8_FF, 0));
+    ///
+    /// instance.render_frame_with(|instance| {
+    ///     // Clears the target, since this is the first time it's selected
+    ///     instance.select_render_target(&target).unwrap();
+    ///     // Doesn't clear again
+    ///     instance.select_render_target(&target).unwrap();
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_default_clear(mut self, spec: ClearSpec) -> Self {
+        self.set_default_clear(Some(spec));
+        self
+    }
+
+    /// Set or remove the clear applied the first time this target is selected
+    /// in each frame. See [`with_default_clear`](Self::with_default_clear).
+    pub fn set_default_clear(&mut self, spec: Option<ClearSpec>) {
+        self.default_clear = spec;
+    }
+
+    /// The clear applied the first time this target is selected in each frame,
+    /// if any.
+    pub fn default_clear(&self) -> Option<ClearSpec> {
+        self.default_clear
+    }
+
+    /// Apply the default clear, unless it was already applied in `frame`.
+    pub(crate) fn auto_clear(&self, frame: u64) {
+        let Some(spec) = self.default_clear else {
+            return;
+        };
+
+        if self.cleared_frame.replace(Some(frame)) != Some(frame) {
+            unsafe {
+                citro3d_sys::C3D_RenderTargetClear(
+                    self.raw,
+                    spec.flags.bits(),
+                    spec.color,
+                    spec.depth,
+                );
+            }
+        }
+    }
+
     /// Return the underlying `citro3d` render target for this target.
     pub(crate) fn as_raw(&self) -> *mut C3D_RenderTarget {
         self.raw
     }
 }
 
+/// Render targets for every output of the 3DS screens, as created by
+/// [`Instance::screen_targets`](crate::Instance::screen_targets).
+pub struct ScreenTargets<'screen> {
+    /// The left side of the top screen. This is the only side of the top screen
+    /// that is displayed when 3D is disabled.
+    pub top_left: Target<'screen>,
+    /// The right side of the top screen, used for stereoscopic 3D.
+    pub top_right: Target<'screen>,
+    /// The bottom screen.
+    pub bottom: Target<'screen>,
+}
+
 bitflags::bitflags! {
     /// Indicate whether color, depth buffer, or both values should be cleared.
     #[doc(alias = "C3D_ClearBits")]
@@ -110,6 +222,464 @@ bitflags::bitflags! {
     }
 }
 
+/// A complete description of how to clear a render target, which can be
+/// reused across targets with [`Target::clear_with`] or set as a target's
+/// [default clear](Target::with_default_clear).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClearSpec {
+    /// Whether color and/or depth should be overwritten.
+    pub flags: ClearFlags,
+    /// The 32-bit RGBA color to clear to.
+    pub color: u32,
+    /// The depth buffer value to clear to.
+    pub depth: u32,
+}
+
+impl ClearSpec {
+    /// Describe a clear with the given flags, 32-bit RGBA color, and depth
+    /// buffer value.
+    pub fn new(flags: ClearFlags, color: u32, depth: u32) -> Self {
+        Self {
+            flags,
+            color,
+            depth,
+        }
+    }
+}
+
+/// How depth values are mapped into the depth buffer, set with
+/// [`Instance::set_depth_range`](crate::Instance::set_depth_range).
+///
+/// The projection matrices produced by [`math::Projection`](crate::math::Projection)
+/// (and by `citro3d` in general) put the near plane at a normalized Z of `-1.0`
+/// and the far plane at `0.0`. A `DepthRange` describes which depth buffer
+/// values those planes end up at, which should be chosen along with the depth
+/// test function.
+///
+/// The default is [`DepthRange::REVERSED`], which is what `citro3d` uses
+/// unless told otherwise.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[doc(alias = "C3D_DepthMap")]
+pub struct DepthRange {
+    /// The depth buffer value at the near plane.
+    pub near_val: f32,
+    /// The depth buffer value at the far plane.
+    pub far_val: f32,
+}
+
+impl DepthRange {
+    /// Reversed depth: the near plane is at `1.0` and the far plane is at
+    /// `0.0`, so closer fragments have *greater* depth values. This spreads
+    /// depth precision more evenly for perspective projections.
+    pub const REVERSED: Self = Self {
+        near_val: 1.0,
+        far_val: 0.0,
+    };
+
+    /// The OpenGL and Direct3D convention: the near plane is at `0.0` and the
+    /// far plane is at `1.0`, so closer fragments have *smaller* depth values.
+    pub const STANDARD: Self = Self {
+        near_val: 0.0,
+        far_val: 1.0,
+    };
+
+    /// Get the `(scale, offset)` passed to `C3D_DepthMap` for this range.
+    pub(crate) fn scale_offset(self) -> (f32, f32) {
+        // The far plane (Z = 0) maps to the offset, and the near plane
+        // (Z = -1) to offset - scale.
+        (self.far_val - self.near_val, self.far_val)
+    }
+}
+
+impl Default for DepthRange {
+    fn default() -> Self {
+        Self::REVERSED
+    }
+}
+
+bitflags::bitflags! {
+    /// Options for how a frame begins, used in [`FrameConfig`].
+    #[doc(alias = "C3D_FrameBegin")]
+    pub struct FrameFlags: u8 {
+        /// Pace frames to the display by waiting for the next frame time
+        /// (via `C3D_FrameSync`) before starting this one. Without this flag,
+        /// frames are not limited to the display's frame rate.
+        const SYNC_DRAW = citro3d_sys::C3D_FRAME_SYNCDRAW as u8;
+        /// Don't block if the GPU is still busy with the previous frame;
+        /// instead, skip rendering this frame. Without this flag, beginning a
+        /// frame always waits for the previous frame's GPU commands to finish.
+        const NON_BLOCK = citro3d_sys::C3D_FRAME_NONBLOCK as u8;
+    }
+}
+
+bitflags::bitflags! {
+    /// Options for how a frame's commands are submitted to the GPU, used in
+    /// [`FrameConfig`] and [`Instance::split_frame`](crate::Instance::split_frame).
+    /// These are forwarded to GX command list processing.
+    #[doc(alias = "C3D_FrameEnd")]
+    #[doc(alias = "GX_ProcessCommandList")]
+    pub struct FrameEndFlags: u8 {
+        /// Update the gas additive blend results after processing the
+        /// command list.
+        #[doc(alias = "GX_CMDLIST_BIT0")]
+        const UPDATE_GAS_RESULTS = 1 << 0;
+        /// Flush the command list from the data cache before processing it.
+        #[doc(alias = "GX_CMDLIST_FLUSH")]
+        const FLUSH = 1 << 1;
+    }
+}
+
+/// How a frame is started and finished by
+/// [`Instance::try_render_frame_with`](crate::Instance::try_render_frame_with).
+///
+/// The default is a blocking frame with [`FrameFlags::SYNC_DRAW`], which is
+/// what [`Instance::render_frame_with`](crate::Instance::render_frame_with) uses.
+/// That is, each frame waits for the GPU to finish the previous one, and is
+/// limited to the display's frame rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameConfig {
+    /// The flags passed to `C3D_FrameBegin`.
+    pub begin: FrameFlags,
+    /// The flags passed to `C3D_FrameEnd`.
+    pub end: FrameEndFlags,
+}
+
+impl FrameConfig {
+    /// A frame which is skipped, instead of stalling the CPU, if the GPU is
+    /// still busy with the previous one.
+    ///
+    /// This does not include [`FrameFlags::SYNC_DRAW`], so frames are also no
+    /// longer limited to the display's frame rate. Add it to `begin` to keep
+    /// the frame limiting.
+    pub const NON_BLOCKING: Self = Self {
+        begin: FrameFlags::NON_BLOCK,
+        end: FrameEndFlags::empty(),
+    };
+}
+
+impl Default for FrameConfig {
+    fn default() -> Self {
+        Self {
+            begin: FrameFlags::SYNC_DRAW,
+            end: FrameEndFlags::empty(),
+        }
+    }
+}
+
+/// A rectangle in render target coordinates, used for the
+/// [viewport](crate::Instance::set_viewport) and
+/// [scissor](crate::Instance::set_scissor).
+///
+/// Note that the 3DS screens are rotated, so these are framebuffer
+/// coordinates: `x` runs along the short (240 pixel) side of the screen, and
+/// `y` along the long side.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// The offset of the rectangle along the framebuffer's width.
+    pub x: u32,
+    /// The offset of the rectangle along the framebuffer's height.
+    pub y: u32,
+    /// The width of the rectangle.
+    pub width: u32,
+    /// The height of the rectangle.
+    pub height: u32,
+}
+
+impl Rect {
+    /// Create a rectangle from its offset and size.
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Which fragments the scissor test keeps.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[doc(alias = "GPU_SCISSORMODE")]
+pub enum ScissorMode {
+    /// Only draw inside the scissor rectangle.
+    #[doc(alias = "GPU_SCISSOR_NORMAL")]
+    Normal = ctru_sys::GPU_SCISSOR_NORMAL,
+    /// Only draw outside the scissor rectangle.
+    #[doc(alias = "GPU_SCISSOR_INVERT")]
+    Invert = ctru_sys::GPU_SCISSOR_INVERT,
+}
+
+/// A comparison used by the GPU's per-fragment tests, where the fragment's
+/// value is compared against a reference value.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[doc(alias = "GPU_TESTFUNC")]
+pub enum TestFunction {
+    /// Never pass.
+    #[doc(alias = "GPU_NEVER")]
+    Never = ctru_sys::GPU_NEVER,
+    /// Always pass.
+    #[doc(alias = "GPU_ALWAYS")]
+    Always = ctru_sys::GPU_ALWAYS,
+    /// Pass if the values are equal.
+    #[doc(alias = "GPU_EQUAL")]
+    Equal = ctru_sys::GPU_EQUAL,
+    /// Pass if the values are not equal.
+    #[doc(alias = "GPU_NOTEQUAL")]
+    NotEqual = ctru_sys::GPU_NOTEQUAL,
+    /// Pass if the fragment's value is less than the reference.
+    #[doc(alias = "GPU_LESS")]
+    Less = ctru_sys::GPU_LESS,
+    /// Pass if the fragment's value is less than or equal to the reference.
+    #[doc(alias = "GPU_LEQUAL")]
+    LessOrEqual = ctru_sys::GPU_LEQUAL,
+    /// Pass if the fragment's value is greater than the reference.
+    #[doc(alias = "GPU_GREATER")]
+    Greater = ctru_sys::GPU_GREATER,
+    /// Pass if the fragment's value is greater than or equal to the reference.
+    #[doc(alias = "GPU_GEQUAL")]
+    GreaterOrEqual = ctru_sys::GPU_GEQUAL,
+}
+
+/// What to do with the stencil buffer value of a fragment, depending on the
+/// outcome of the stencil and depth tests.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[doc(alias = "GPU_STENCILOP")]
+pub enum StencilOperation {
+    /// Keep the current value.
+    #[doc(alias = "GPU_STENCIL_KEEP")]
+    Keep = ctru_sys::GPU_STENCIL_KEEP,
+    /// Set the value to zero.
+    #[doc(alias = "GPU_STENCIL_ZERO")]
+    Zero = ctru_sys::GPU_STENCIL_ZERO,
+    /// Set the value to the reference value.
+    #[doc(alias = "GPU_STENCIL_REPLACE")]
+    Replace = ctru_sys::GPU_STENCIL_REPLACE,
+    /// Increment the value, saturating at the maximum.
+    #[doc(alias = "GPU_STENCIL_INCR")]
+    Increment = ctru_sys::GPU_STENCIL_INCR,
+    /// Decrement the value, saturating at zero.
+    #[doc(alias = "GPU_STENCIL_DECR")]
+    Decrement = ctru_sys::GPU_STENCIL_DECR,
+    /// Invert the bits of the value.
+    #[doc(alias = "GPU_STENCIL_INVERT")]
+    Invert = ctru_sys::GPU_STENCIL_INVERT,
+    /// Increment the value, wrapping around to zero.
+    #[doc(alias = "GPU_STENCIL_INCR_WRAP")]
+    IncrementWrap = ctru_sys::GPU_STENCIL_INCR_WRAP,
+    /// Decrement the value, wrapping around to the maximum.
+    #[doc(alias = "GPU_STENCIL_DECR_WRAP")]
+    DecrementWrap = ctru_sys::GPU_STENCIL_DECR_WRAP,
+}
+
+/// The stencil test and stencil buffer updates, set with
+/// [`Instance::set_stencil`](crate::Instance::set_stencil). This requires a
+/// render target with a [`DepthFormat::Depth24Stencil8`] depth buffer.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::render::{StencilConfig, StencilOperation, TestFunction};
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// // Mark every drawn pixel with 1 in the stencil buffer...
+/// let mut mask = StencilConfig::new(TestFunction::Always, 1);
+/// mask.on_pass = StencilOperation::Replace;
+/// instance.set_stencil(Some(mask));
+///
+/// // ...then only draw where the mask was drawn
+/// instance.set_stencil(Some(StencilConfig::new(TestFunction::Equal, 1)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StencilConfig {
+    /// How `reference` is compared to the stencil buffer value.
+    pub function: TestFunction,
+    /// The reference value.
+    pub reference: u8,
+    /// The bits of the reference and stencil buffer values used in the test.
+    pub test_mask: u8,
+    /// The bits of the stencil buffer which may be written.
+    pub write_mask: u8,
+    /// What to do when the stencil test fails.
+    pub on_stencil_fail: StencilOperation,
+    /// What to do when the stencil test passes but the depth test fails.
+    pub on_depth_fail: StencilOperation,
+    /// What to do when both tests pass.
+    pub on_pass: StencilOperation,
+}
+
+impl StencilConfig {
+    /// Test against `reference` with all bits, keeping the stencil buffer
+    /// unchanged whatever the result.
+    pub fn new(function: TestFunction, reference: u8) -> Self {
+        Self {
+            function,
+            reference,
+            test_mask: 0xFF,
+            write_mask: 0xFF,
+            on_stencil_fail: StencilOperation::Keep,
+            on_depth_fail: StencilOperation::Keep,
+            on_pass: StencilOperation::Keep,
+        }
+    }
+}
+
+/// How the source (fragment) and destination (framebuffer) terms are combined
+/// when blending.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[doc(alias = "GPU_BLENDEQUATION")]
+pub enum BlendEquation {
+    /// `source + destination`.
+    #[doc(alias = "GPU_BLEND_ADD")]
+    Add = ctru_sys::GPU_BLEND_ADD,
+    /// `source - destination`.
+    #[doc(alias = "GPU_BLEND_SUBTRACT")]
+    Subtract = ctru_sys::GPU_BLEND_SUBTRACT,
+    /// `destination - source`.
+    #[doc(alias = "GPU_BLEND_REVERSE_SUBTRACT")]
+    ReverseSubtract = ctru_sys::GPU_BLEND_REVERSE_SUBTRACT,
+    /// The smaller of the two values. The blend factors are ignored.
+    #[doc(alias = "GPU_BLEND_MIN")]
+    Min = ctru_sys::GPU_BLEND_MIN,
+    /// The larger of the two values. The blend factors are ignored.
+    #[doc(alias = "GPU_BLEND_MAX")]
+    Max = ctru_sys::GPU_BLEND_MAX,
+}
+
+/// The factor the source or destination values are multiplied by when
+/// blending.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[doc(alias = "GPU_BLENDFACTOR")]
+pub enum BlendFactor {
+    /// `0`.
+    #[doc(alias = "GPU_ZERO")]
+    Zero = ctru_sys::GPU_ZERO,
+    /// `1`.
+    #[doc(alias = "GPU_ONE")]
+    One = ctru_sys::GPU_ONE,
+    /// The source (fragment) color.
+    #[doc(alias = "GPU_SRC_COLOR")]
+    SrcColor = ctru_sys::GPU_SRC_COLOR,
+    /// `1` minus the source color.
+    #[doc(alias = "GPU_ONE_MINUS_SRC_COLOR")]
+    OneMinusSrcColor = ctru_sys::GPU_ONE_MINUS_SRC_COLOR,
+    /// The destination (framebuffer) color.
+    #[doc(alias = "GPU_DST_COLOR")]
+    DstColor = ctru_sys::GPU_DST_COLOR,
+    /// `1` minus the destination color.
+    #[doc(alias = "GPU_ONE_MINUS_DST_COLOR")]
+    OneMinusDstColor = ctru_sys::GPU_ONE_MINUS_DST_COLOR,
+    /// The source alpha.
+    #[doc(alias = "GPU_SRC_ALPHA")]
+    SrcAlpha = ctru_sys::GPU_SRC_ALPHA,
+    /// `1` minus the source alpha.
+    #[doc(alias = "GPU_ONE_MINUS_SRC_ALPHA")]
+    OneMinusSrcAlpha = ctru_sys::GPU_ONE_MINUS_SRC_ALPHA,
+    /// The destination alpha.
+    #[doc(alias = "GPU_DST_ALPHA")]
+    DstAlpha = ctru_sys::GPU_DST_ALPHA,
+    /// `1` minus the destination alpha.
+    #[doc(alias = "GPU_ONE_MINUS_DST_ALPHA")]
+    OneMinusDstAlpha = ctru_sys::GPU_ONE_MINUS_DST_ALPHA,
+    /// The [constant color](Blend::constant).
+    #[doc(alias = "GPU_CONSTANT_COLOR")]
+    ConstantColor = ctru_sys::GPU_CONSTANT_COLOR,
+    /// `1` minus the [constant color](Blend::constant).
+    #[doc(alias = "GPU_ONE_MINUS_CONSTANT_COLOR")]
+    OneMinusConstantColor = ctru_sys::GPU_ONE_MINUS_CONSTANT_COLOR,
+    /// The alpha of the [constant color](Blend::constant).
+    #[doc(alias = "GPU_CONSTANT_ALPHA")]
+    ConstantAlpha = ctru_sys::GPU_CONSTANT_ALPHA,
+    /// `1` minus the alpha of the [constant color](Blend::constant).
+    #[doc(alias = "GPU_ONE_MINUS_CONSTANT_ALPHA")]
+    OneMinusConstantAlpha = ctru_sys::GPU_ONE_MINUS_CONSTANT_ALPHA,
+    /// The smaller of the source alpha and `1` minus the destination
+    /// alpha. The alpha channel always uses `1`.
+    #[doc(alias = "GPU_SRC_ALPHA_SATURATE")]
+    SrcAlphaSaturate = ctru_sys::GPU_SRC_ALPHA_SATURATE,
+}
+
+/// The alpha blending configuration, set with
+/// [`Instance::set_blend`](crate::Instance::set_blend).
+///
+/// The color and alpha channels are each computed as
+/// `equation(source * src_factor, destination * dst_factor)`.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::render::Blend;
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// // Glowing particles which brighten whatever is behind them
+/// instance.set_blend(Blend::ADDITIVE);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[doc(alias = "C3D_AlphaBlend")]
+pub struct Blend {
+    /// The equation used for the red, green, and blue channels.
+    pub color_equation: BlendEquation,
+    /// The equation used for the alpha channel.
+    pub alpha_equation: BlendEquation,
+    /// The factor applied to the fragment's color.
+    pub src_color: BlendFactor,
+    /// The factor applied to the framebuffer's color.
+    pub dst_color: BlendFactor,
+    /// The factor applied to the fragment's alpha.
+    pub src_alpha: BlendFactor,
+    /// The factor applied to the framebuffer's alpha.
+    pub dst_alpha: BlendFactor,
+    /// The color used by the `Constant*` blend factors.
+    #[doc(alias = "C3D_BlendingColor")]
+    pub constant: Color,
+}
+
+impl Blend {
+    /// Standard alpha blending, where the fragment is drawn over the
+    /// framebuffer according to its alpha. This is the default.
+    pub const ALPHA: Self = Self::new(
+        BlendEquation::Add,
+        BlendFactor::SrcAlpha,
+        BlendFactor::OneMinusSrcAlpha,
+    );
+
+    /// Blending for colors which are already multiplied by their alpha.
+    pub const PREMULTIPLIED: Self = Self::new(
+        BlendEquation::Add,
+        BlendFactor::One,
+        BlendFactor::OneMinusSrcAlpha,
+    );
+
+    /// Additive blending, where the fragment's color (scaled by its alpha) is
+    /// added to the framebuffer.
+    pub const ADDITIVE: Self =
+        Self::new(BlendEquation::Add, BlendFactor::SrcAlpha, BlendFactor::One);
+
+    /// Blending with the same equation and factors for both the color and
+    /// alpha channels, and a transparent constant color.
+    pub const fn new(equation: BlendEquation, src: BlendFactor, dst: BlendFactor) -> Self {
+        Self {
+            color_equation: equation,
+            alpha_equation: equation,
+            src_color: src,
+            dst_color: dst,
+            src_alpha: src,
+            dst_alpha: dst,
+            constant: Color::TRANSPARENT,
+        }
+    }
+}
+
+impl Default for Blend {
+    fn default() -> Self {
+        Self::ALPHA
+    }
+}
+
 /// The color format to use when rendering on the GPU.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug)]
@@ -161,3 +731,15 @@ impl DepthFormat {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_range_matches_citro3d_default() {
+        // citro3d initializes the depth map with C3D_DepthMap(true, -1.0, 0.0)
+        assert_eq!(DepthRange::default().scale_offset(), (-1.0, 0.0));
+        assert_eq!(DepthRange::STANDARD.scale_offset(), (1.0, 1.0));
+    }
+}
@@ -5,11 +5,18 @@
 //! documentation for <https://github.com/devkitPro/picasso>.
 
 use std::error::Error;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::fs;
 use std::mem::MaybeUninit;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::uniform;
 
+#[cfg(feature = "builtin-shaders")]
+pub mod builtin;
+pub mod lint;
+
 /// A PICA200 shader program. It may have one or both of:
 ///
 /// * A [vertex](Type::Vertex) shader [`Library`]
@@ -74,6 +81,60 @@ impl Program {
         }
     }
 
+    /// Set the geometry shader for a given program, along with the layout of
+    /// the vertices it receives as input. See [`GeometryInput`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input shader is not a geometry shader or is
+    /// otherwise invalid.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::{attrib, shader};
+    /// # fn example(
+    /// #     program: &mut shader::Program,
+    /// #     geometry_shader: shader::Entrypoint,
+    /// #     attr_info: &attrib::Info,
+    /// # ) {
+    /// // Swap the first two attributes of each vertex
+    /// let mut permutation = shader::GeometryInput::IDENTITY;
+    /// permutation.swap(0, 1);
+    ///
+    /// let input = shader::GeometryInput::from_attrib_info(attr_info)
+    ///     .and_then(|input| input.permutation(permutation))
+    ///     .unwrap();
+    ///
+    /// program
+    ///     .set_geometry_shader_with(geometry_shader, input)
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[doc(alias = "shaderProgramSetGsh")]
+    #[doc(alias = "shaderProgramSetGshInputPermutation")]
+    pub fn set_geometry_shader_with(
+        &mut self,
+        geometry_shader: Entrypoint,
+        input: GeometryInput,
+    ) -> Result<(), ctru::Error> {
+        self.set_geometry_shader(geometry_shader, input.stride)?;
+
+        let ret = unsafe {
+            ctru_sys::shaderProgramSetGshInputPermutation(
+                &mut self.program,
+                input.raw_permutation(),
+            )
+        };
+
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(ctru::Error::from(ret))
+        }
+    }
+
     /// Get the index of a uniform by name.
     ///
     /// # Errors
@@ -100,11 +161,345 @@ impl Program {
         }
     }
 
+    /// Get the range of registers reserved for a uniform by name. Unlike
+    /// [`Program::get_uniform`], this can be used to check that a value fits
+    /// the uniform before binding it, e.g. with
+    /// [`Instance::bind_vertex_uniform_range`](crate::Instance::bind_vertex_uniform_range).
+    ///
+    /// # Errors
+    ///
+    /// * If a uniform with the given `name` could not be found
+    pub fn get_uniform_range(&self, name: &str) -> crate::Result<uniform::IndexRange> {
+        self.uniforms()
+            .find(|info| info.name == name)
+            .map(|info| info.range())
+            .ok_or(crate::Error::NotFound)
+    }
+
+    /// Iterate over the uniforms declared by the program's vertex shader.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::macros::include_shader;
+    /// # use citro3d::shader;
+    /// # static SHADER_BYTES: &[u8] = include_shader!("../examples/assets/vshader.pica");
+    /// let library = shader::Library::from_bytes(SHADER_BYTES).unwrap();
+    /// let program = shader::Program::new(library.get(0).unwrap()).unwrap();
+    ///
+    /// let names: Vec<_> = program.uniforms().map(|u| u.name).collect();
+    /// assert_eq!(names, ["projection"]);
+    /// ```
+    #[doc(alias = "uniformTableData")]
+    pub fn uniforms(&self) -> impl Iterator<Item = UniformInfo<'_>> + '_ {
+        let vertex_instance = unsafe { (*self.as_raw()).vertexShader };
+        assert!(
+            !vertex_instance.is_null(),
+            "vertex shader should never be null!"
+        );
+
+        // SAFETY: the instance's DVLE is valid for as long as the program is
+        unsafe { uniform_table((*vertex_instance).dvle) }
+    }
+
     pub(crate) fn as_raw(&self) -> *const ctru_sys::shaderProgram_s {
         &self.program
     }
 }
 
+/// Information about a uniform declared by a shader, as found in its
+/// [uniform table](https://www.3dbrew.org/wiki/SHBIN#Uniform_Table_Entry).
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct UniformInfo<'a> {
+    /// The name of the uniform.
+    pub name: &'a str,
+    /// The index of the uniform's first register, for use when binding it.
+    pub index: uniform::Index,
+    /// The number of registers the uniform uses, e.g. 4 for a matrix.
+    pub len: usize,
+    /// The kind of registers the uniform uses.
+    pub kind: uniform::Kind,
+}
+
+impl UniformInfo<'_> {
+    /// The registers reserved for the uniform.
+    pub fn range(&self) -> uniform::IndexRange {
+        // UNWRAP: the uniform table only contains ranges of valid registers
+        uniform::IndexRange::new(self.index, self.len as u8).unwrap()
+    }
+}
+
+/// Iterate over the uniform table of a DVLE.
+///
+/// # Safety
+///
+/// `dvle` must point to a valid DVLE which outlives `'a`.
+unsafe fn uniform_table<'a>(
+    dvle: *const ctru_sys::DVLE_s,
+) -> impl Iterator<Item = UniformInfo<'a>> + 'a {
+    let dvle = &*dvle;
+    let entries = dvle_table(dvle.uniformTableData, dvle.uniformTableSize);
+    let symbols = dvle.symbolTableData;
+
+    entries.iter().filter_map(move |entry| {
+        // Uniform names are always ASCII identifiers, so anything else is
+        // a malformed entry and can be skipped.
+        let name = CStr::from_ptr(symbols.add(entry.symbolOffset as usize))
+            .to_str()
+            .ok()?;
+
+        // libctru excludes the 16 input registers from uniform indices, see
+        // `shaderInstanceGetUniformLocation`
+        let index = u8::try_from(entry.startReg.checked_sub(0x10)?).ok()?;
+        let len = usize::from(entry.endReg.checked_sub(entry.startReg)?) + 1;
+
+        Some(UniformInfo {
+            name,
+            index: index.into(),
+            len,
+            kind: uniform::Kind::of(index.into()),
+        })
+    })
+}
+
+/// A constant declared by a shader (e.g. with `.constf`), as found in its
+/// [constant table](https://www.3dbrew.org/wiki/SHBIN#Constant_Table_Entry).
+/// These are loaded into their registers whenever the shader is bound.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum Constant {
+    /// A floating-point vector constant (`.constf`).
+    Float {
+        index: uniform::Index,
+        value: crate::math::FVec4,
+    },
+    /// An integer vector constant (`.consti`).
+    Int {
+        index: uniform::IntIndex,
+        value: crate::math::IVec,
+    },
+    /// A boolean constant (`.constb`).
+    Bool {
+        index: uniform::BoolIndex,
+        value: bool,
+    },
+}
+
+/// The kind of data a shader writes to an output register, as declared with
+/// `.out`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputKind {
+    /// The vertex position.
+    Position,
+    /// The normal quaternion, used for fragment lighting.
+    NormalQuaternion,
+    /// The vertex color.
+    Color,
+    /// Texture coordinates for texture unit 0.
+    TexCoord0,
+    /// The W component of texture coordinates for texture unit 0, used for
+    /// cube maps and projected textures.
+    TexCoord0W,
+    /// Texture coordinates for texture unit 1.
+    TexCoord1,
+    /// Texture coordinates for texture unit 2.
+    TexCoord2,
+    /// The view vector, used for fragment lighting.
+    View,
+    /// An output that is not used by the fixed-function pipeline.
+    Dummy,
+}
+
+/// An output register written by a shader, as found in its
+/// [output table](https://www.3dbrew.org/wiki/SHBIN#Output_Register_Table_Entry).
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct OutputInfo {
+    /// The kind of data written to the register.
+    pub kind: OutputKind,
+    /// The index of the output register (`o0` to `o15`).
+    pub register: u8,
+    /// The components of the register used by this output, with one bit per
+    /// component starting from `x` in the least significant bit.
+    pub mask: u8,
+}
+
+/// Get a table of a DVLE as a slice.
+///
+/// # Safety
+///
+/// `data` must point to `len` valid entries which outlive `'a`, unless `len` is zero.
+unsafe fn dvle_table<'a, T>(data: *const T, len: u32) -> &'a [T] {
+    if len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data, len as usize)
+    }
+}
+
+/// Convert a PICA200 24-bit float (1 sign bit, 7 exponent bits, 16 mantissa
+/// bits) into an `f32`.
+fn f24_to_f32(bits: u32) -> f32 {
+    let sign = (bits >> 23) & 0x1;
+    let exponent = (bits >> 16) & 0x7F;
+    let mantissa = bits & 0xFFFF;
+
+    let exponent = match exponent {
+        // Denormals are flushed to zero, like the GPU does
+        0 => return if sign == 0 { 0.0 } else { -0.0 },
+        0x7F => 0xFF,
+        _ => exponent + 127 - 63,
+    };
+
+    f32::from_bits((sign << 31) | (exponent << 23) | (mantissa << 7))
+}
+
+/// Iterate over the constant table of a DVLE.
+///
+/// # Safety
+///
+/// `dvle` must point to a valid DVLE which outlives `'a`.
+unsafe fn constant_table<'a>(dvle: *const ctru_sys::DVLE_s) -> impl Iterator<Item = Constant> + 'a {
+    let dvle = &*dvle;
+    let entries = dvle_table(dvle.constTableData, dvle.constTableSize);
+
+    entries.iter().filter_map(|entry| {
+        let id = u8::try_from(entry.id).ok()?;
+        let [x, y, z, w] = entry.data;
+
+        let constant = match u32::from(entry.type_) {
+            ctru_sys::DVLE_CONST_FLOAT24 => Constant::Float {
+                index: id.into(),
+                value: crate::math::FVec4::new(
+                    f24_to_f32(x),
+                    f24_to_f32(y),
+                    f24_to_f32(z),
+                    f24_to_f32(w),
+                ),
+            },
+            ctru_sys::DVLE_CONST_u8 => Constant::Int {
+                index: uniform::IntIndex::new(id)?,
+                value: crate::math::IVec::new(x as u8, y as u8, z as u8, w as u8),
+            },
+            ctru_sys::DVLE_CONST_BOOL => Constant::Bool {
+                index: uniform::BoolIndex::new(id)?,
+                value: x & 1 != 0,
+            },
+            _ => return None,
+        };
+
+        Some(constant)
+    })
+}
+
+/// Iterate over the output table of a DVLE.
+///
+/// # Safety
+///
+/// `dvle` must point to a valid DVLE which outlives `'a`.
+unsafe fn output_table<'a>(dvle: *const ctru_sys::DVLE_s) -> impl Iterator<Item = OutputInfo> + 'a {
+    let dvle = &*dvle;
+    let entries = dvle_table(dvle.outTableData, dvle.outTableSize);
+
+    entries.iter().filter_map(|entry| {
+        let kind = match u32::from(entry.type_) {
+            ctru_sys::RESULT_POSITION => OutputKind::Position,
+            ctru_sys::RESULT_NORMALQUAT => OutputKind::NormalQuaternion,
+            ctru_sys::RESULT_COLOR => OutputKind::Color,
+            ctru_sys::RESULT_TEXCOORD0 => OutputKind::TexCoord0,
+            ctru_sys::RESULT_TEXCOORD0W => OutputKind::TexCoord0W,
+            ctru_sys::RESULT_TEXCOORD1 => OutputKind::TexCoord1,
+            ctru_sys::RESULT_TEXCOORD2 => OutputKind::TexCoord2,
+            ctru_sys::RESULT_VIEW => OutputKind::View,
+            ctru_sys::RESULT_DUMMY => OutputKind::Dummy,
+            _ => return None,
+        };
+
+        Some(OutputInfo {
+            kind,
+            register: u8::try_from(entry.regID).ok()?,
+            mask: entry.mask,
+        })
+    })
+}
+
+/// The layout of the vertices a geometry shader receives as input, for use
+/// with [`Program::set_geometry_shader_with`].
+///
+/// Each input vertex consists of `stride` registers, which are mapped to the
+/// geometry shader's input registers by a permutation: input register `i`
+/// receives element `permutation[i]` of the vertex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GeometryInput {
+    stride: u8,
+    permutation: [u8; 16],
+}
+
+impl GeometryInput {
+    /// The identity permutation, which maps each element of an input vertex to
+    /// the input register of the same index.
+    pub const IDENTITY: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+    /// Create an input layout of `stride` registers per vertex, using the
+    /// [identity](Self::IDENTITY) permutation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `stride` is zero or greater than 16.
+    pub fn new(stride: u8) -> crate::Result<Self> {
+        if !(1..=16).contains(&stride) {
+            return Err(crate::Error::InvalidSize);
+        }
+
+        Ok(Self {
+            stride,
+            permutation: Self::IDENTITY,
+        })
+    }
+
+    /// Create an input layout with one register per attribute registered in
+    /// the given [`attrib::Info`](crate::attrib::Info).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attribute info has no attributes.
+    pub fn from_attrib_info(info: &crate::attrib::Info) -> crate::Result<Self> {
+        Self::new(info.attr_count().try_into()?)
+    }
+
+    /// Set the permutation used to map vertex elements to input registers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the first `stride` entries of the
+    /// permutation refer to an element outside the vertex, or if any entry
+    /// is greater than 15.
+    pub fn permutation(mut self, permutation: [u8; 16]) -> crate::Result<Self> {
+        let (used, unused) = permutation.split_at(self.stride.into());
+        if used.iter().any(|&p| p >= self.stride) || unused.iter().any(|&p| p >= 16) {
+            return Err(crate::Error::InvalidSize);
+        }
+
+        self.permutation = permutation;
+        Ok(self)
+    }
+
+    /// Get the number of registers per input vertex.
+    pub fn stride(&self) -> u8 {
+        self.stride
+    }
+
+    fn raw_permutation(&self) -> u64 {
+        self.permutation
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, &p)| acc | (u64::from(p) << (4 * i)))
+    }
+}
+
 impl Drop for Program {
     #[doc(alias = "shaderProgramFree")]
     fn drop(&mut self) {
@@ -116,7 +511,7 @@ impl Drop for Program {
 
 /// The type of a shader.
 #[repr(u8)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Type {
     /// A vertex shader.
     Vertex = ctru_sys::GPU_VERTEX_SHADER,
@@ -137,7 +532,10 @@ impl From<Type> for u8 {
 /// This is the result of parsing a shader binary (`.shbin`), and the resulting
 /// [`Entrypoint`]s can be used as part of a [`Program`].
 #[doc(alias = "DVLB_s")]
-pub struct Library(*mut ctru_sys::DVLB_s);
+pub struct Library {
+    ptr: *mut ctru_sys::DVLB_s,
+    names: Vec<Option<String>>,
+}
 
 impl Library {
     /// Parse a new shader library from input bytes.
@@ -149,7 +547,7 @@ impl Library {
     #[doc(alias = "DVLB_ParseFile")]
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
         let aligned: &[u32] = bytemuck::try_cast_slice(bytes)?;
-        Ok(Self(unsafe {
+        let ptr = unsafe {
             ctru_sys::DVLB_ParseFile(
                 // SAFETY: we're trusting the parse implementation doesn't mutate
                 // the contents of the data. From a quick read it looks like that's
@@ -157,14 +555,53 @@ impl Library {
                 aligned.as_ptr().cast_mut(),
                 aligned.len().try_into()?,
             )
-        }))
+        };
+
+        Ok(Self {
+            ptr,
+            names: entrypoint_names(bytes),
+        })
+    }
+
+    /// Parse a new shader library like [`from_bytes`](Self::from_bytes), and
+    /// [check it](lint::check) for common mistakes, including whether it
+    /// declares each of `expected_uniforms`.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`from_bytes`](Self::from_bytes).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::macros::include_shader;
+    /// # use citro3d::shader;
+    /// # static SHADER_BYTES: &[u8] = include_shader!("../examples/assets/vshader.pica");
+    /// let (library, warnings) =
+    ///     shader::Library::from_bytes_checked(SHADER_BYTES, &["projection"]).unwrap();
+    ///
+    /// if cfg!(debug_assertions) {
+    ///     for warning in &warnings {
+    ///         println!("shader warning: {warning}");
+    ///     }
+    /// }
+    /// # assert!(warnings.is_empty());
+    /// ```
+    pub fn from_bytes_checked(
+        bytes: &[u8],
+        expected_uniforms: &[&str],
+    ) -> Result<(Self, Vec<lint::Warning>), Box<dyn Error>> {
+        let library = Self::from_bytes(bytes)?;
+        let warnings = lint::check(&library, expected_uniforms);
+        Ok((library, warnings))
     }
 
     /// Get the number of [`Entrypoint`]s in this shader library.
     #[must_use]
     #[doc(alias = "numDVLE")]
     pub fn len(&self) -> usize {
-        unsafe { (*self.0).numDVLE as usize }
+        unsafe { (*self.ptr).numDVLE as usize }
     }
 
     /// Whether the library has any [`Entrypoint`]s or not.
@@ -178,16 +615,42 @@ impl Library {
     pub fn get(&self, index: usize) -> Option<Entrypoint> {
         if index < self.len() {
             Some(Entrypoint {
-                ptr: unsafe { (*self.0).DVLE.add(index) },
-                _library: self,
+                ptr: unsafe { (*self.ptr).DVLE.add(index) },
+                index,
+                library: self,
             })
         } else {
             None
         }
     }
 
+    /// Get the [`Entrypoint`] with the given name, if present. The name of an
+    /// entrypoint is the name of the procedure it starts at, e.g. `main` for
+    /// the default entrypoint of a `picasso` shader source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::macros::include_shader;
+    /// # use citro3d::shader;
+    /// # static SHADER_BYTES: &[u8] = include_shader!("../examples/assets/vshader.pica");
+    /// let library = shader::Library::from_bytes(SHADER_BYTES).unwrap();
+    /// let vertex_shader = library.get_by_name("main").unwrap();
+    /// assert_eq!(vertex_shader.ty(), shader::Type::Vertex);
+    /// ```
+    #[must_use]
+    pub fn get_by_name(&self, name: &str) -> Option<Entrypoint> {
+        self.entrypoints().find(|entry| entry.name() == Some(name))
+    }
+
+    /// Iterate over the [`Entrypoint`]s in this shader library.
+    pub fn entrypoints(&self) -> impl Iterator<Item = Entrypoint<'_>> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
     fn as_raw(&mut self) -> *mut ctru_sys::DVLB_s {
-        self.0
+        self.ptr
     }
 }
 
@@ -205,11 +668,223 @@ impl Drop for Library {
 #[derive(Clone, Copy)]
 pub struct Entrypoint<'lib> {
     ptr: *mut ctru_sys::DVLE_s,
-    _library: &'lib Library,
+    index: usize,
+    library: &'lib Library,
 }
 
 impl<'lib> Entrypoint<'lib> {
+    /// Get the name of this entrypoint, if the shader binary includes it.
+    /// See [`Library::get_by_name`] for details.
+    #[must_use]
+    pub fn name(self) -> Option<&'lib str> {
+        self.library.names.get(self.index)?.as_deref()
+    }
+
+    /// Get the type of shader this entrypoint contains.
+    #[must_use]
+    pub fn ty(self) -> Type {
+        if unsafe { (*self.ptr).type_ } == ctru_sys::GEOMETRY_SHDR {
+            Type::Geometry
+        } else {
+            Type::Vertex
+        }
+    }
+
+    /// Iterate over the uniforms declared by this entrypoint.
+    #[doc(alias = "uniformTableData")]
+    pub fn uniforms(self) -> impl Iterator<Item = UniformInfo<'lib>> {
+        // SAFETY: the DVLE is valid for as long as the library is
+        unsafe { uniform_table(self.ptr) }
+    }
+
+    /// Iterate over the constants declared by this entrypoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::macros::include_shader;
+    /// # use citro3d::shader::{self, Constant};
+    /// # static SHADER_BYTES: &[u8] = include_shader!("../examples/assets/vshader.pica");
+    /// let library = shader::Library::from_bytes(SHADER_BYTES).unwrap();
+    /// let vertex_shader = library.get(0).unwrap();
+    ///
+    /// let constants: Vec<_> = vertex_shader.constants().collect();
+    /// assert!(matches!(constants[..], [Constant::Float { .. }]));
+    /// ```
+    #[doc(alias = "constTableData")]
+    pub fn constants(self) -> impl Iterator<Item = Constant> + 'lib {
+        // SAFETY: the DVLE is valid for as long as the library is
+        unsafe { constant_table(self.ptr) }
+    }
+
+    /// Iterate over the output registers written by this entrypoint. This can
+    /// be used to determine which registers carry e.g. colors or texture
+    /// coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::macros::include_shader;
+    /// # use citro3d::shader::{self, OutputKind};
+    /// # static SHADER_BYTES: &[u8] = include_shader!("../examples/assets/vshader.pica");
+    /// let library = shader::Library::from_bytes(SHADER_BYTES).unwrap();
+    /// let vertex_shader = library.get(0).unwrap();
+    ///
+    /// let kinds: Vec<_> = vertex_shader.outputs().map(|out| out.kind).collect();
+    /// assert_eq!(kinds, [OutputKind::Position, OutputKind::Color]);
+    /// ```
+    #[doc(alias = "outTableData")]
+    pub fn outputs(self) -> impl Iterator<Item = OutputInfo> + 'lib {
+        // SAFETY: the DVLE is valid for as long as the library is
+        unsafe { output_table(self.ptr) }
+    }
+
     fn as_raw(self) -> *mut ctru_sys::DVLE_s {
         self.ptr
     }
 }
+
+/// Find the name of each DVLE in a shader binary, which is the label located at
+/// the DVLE's main offset. See <https://www.3dbrew.org/wiki/SHBIN> for details
+/// of the format. Any names that cannot be found are left as `None`.
+fn entrypoint_names(bytes: &[u8]) -> Vec<Option<String>> {
+    let read_u32 = |offset: usize| -> Option<usize> {
+        let field = bytes.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes(field.try_into().ok()?) as usize)
+    };
+
+    let name_of = |dvle: usize| -> Option<String> {
+        let main_offset = read_u32(dvle + 0x8)?;
+        let label_table = dvle + read_u32(dvle + 0x20)?;
+        let label_count = read_u32(dvle + 0x24)?;
+        let symbol_table = dvle + read_u32(dvle + 0x38)?;
+
+        let symbol_offset = (0..label_count)
+            .map(|i| label_table + 0x10 * i)
+            .find(|&label| read_u32(label + 0x4) == Some(main_offset))
+            .and_then(|label| read_u32(label + 0xC))?;
+
+        let name = CStr::from_bytes_until_nul(bytes.get(symbol_table + symbol_offset..)?).ok()?;
+        Some(name.to_str().ok()?.to_owned())
+    };
+
+    let dvle_count = read_u32(0x4).unwrap_or_default();
+    (0..dvle_count)
+        .map(|i| read_u32(0x8 + 4 * i).and_then(name_of))
+        .collect()
+}
+
+/// A shader [`Program`] loaded from a compiled shader binary (`.shbin`) on the
+/// filesystem at runtime, which can be reloaded whenever the file changes. This
+/// is useful for iterating on shaders without rebuilding the application.
+///
+/// The program is built from the first [`Entrypoint`] of the shader binary,
+/// which must be a vertex shader.
+///
+/// # Example
+///
+/// ```no_run
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::shader;
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// let mut watcher = shader::Watcher::new("sdmc:/shaders/vshader.shbin").unwrap();
+/// instance.bind_program(watcher.program());
+///
+/// // Once per frame, before drawing anything:
+/// if watcher.poll().unwrap() {
+///     instance.bind_program(watcher.program());
+/// }
+/// ```
+pub struct Watcher {
+    // NOTE: field order matters here, since the program points into the library,
+    // which points into the shader binary data.
+    program: Program,
+    _library: Library,
+    _data: Vec<u32>,
+    path: PathBuf,
+    version: Option<FileVersion>,
+}
+
+/// Metadata used to determine whether a watched file has changed.
+type FileVersion = (u64, Option<SystemTime>);
+
+impl Watcher {
+    /// Load the shader binary at the given path and build a program from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or the shader binary or
+    /// program is invalid.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let path = path.into();
+        let version = file_version(&path);
+        let (program, library, data) = load_program(&path)?;
+
+        Ok(Self {
+            program,
+            _library: library,
+            _data: data,
+            path,
+            version,
+        })
+    }
+
+    /// Get the most recently loaded program.
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Check whether the shader binary has changed on disk, and reload the
+    /// program if it has. Changes are detected using the file's size and
+    /// modification time.
+    ///
+    /// Returns `true` if the program was reloaded. In that case, the previous
+    /// program has been freed, so the new [`program`](Self::program) **must**
+    /// be bound with [`Instance::bind_program`](crate::Instance::bind_program)
+    /// before drawing anything else.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the changed file cannot be loaded. The previously
+    /// loaded program is kept in that case, and loading will be retried the
+    /// next time the file changes.
+    pub fn poll(&mut self) -> Result<bool, Box<dyn Error>> {
+        let version = file_version(&self.path);
+        if version == self.version {
+            return Ok(false);
+        }
+
+        self.version = version;
+        let (program, library, data) = load_program(&self.path)?;
+
+        // Assigning the fields in order ensures the old program is dropped
+        // before the library and data it refers to.
+        self.program = program;
+        self._library = library;
+        self._data = data;
+
+        Ok(true)
+    }
+}
+
+fn file_version(path: &Path) -> Option<FileVersion> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()))
+}
+
+fn load_program(path: &Path) -> Result<(Program, Library, Vec<u32>), Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+
+    // The library refers to the shader binary data after parsing, and the data
+    // must be 4-byte aligned, so copy it into an owned buffer of words.
+    let mut data = vec![0u32; bytes.len().div_ceil(4)];
+    bytemuck::cast_slice_mut::<u32, u8>(&mut data)[..bytes.len()].copy_from_slice(&bytes);
+
+    let library = Library::from_bytes(bytemuck::cast_slice(&data))?;
+    let vertex_shader = library.get(0).ok_or("shader binary has no entrypoints")?;
+    let program = Program::new(vertex_shader)?;
+
+    Ok((program, library, data))
+}
@@ -0,0 +1,138 @@
+//! Precompiled utility shaders that ship with the crate, so common effects work
+//! without providing `.pica` sources. These are compiled with `picasso` when the
+//! `builtin-shaders` feature is enabled.
+//!
+//! * [`PASSTHROUGH`] and [`FULLSCREEN_TRIANGLE`] for post-processing effects.
+//! * [`POINT_SPRITE`] expands points into quads, e.g. for particles.
+//! * [`WIDE_LINE`] expands lines into quads, e.g. for debug drawing.
+
+use super::GeometryInput;
+use crate::attrib::{self, Attribute, Format, Register};
+
+/// A vertex shader which passes clip-space positions (input register 0) and
+/// texture coordinates (input register 1) through unchanged. Use this with
+/// [`Vertex`] data, e.g. [`FULLSCREEN_TRIANGLE`].
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::shader::{self, builtin};
+/// let library = shader::Library::from_bytes(builtin::PASSTHROUGH).unwrap();
+/// let program = shader::Program::new(library.get(0).unwrap()).unwrap();
+/// ```
+pub static PASSTHROUGH: &[u8] = citro3d_macros::include_shader!("builtin/passthrough.pica");
+
+/// A vertex for the [`PASSTHROUGH`] shader.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vertex {
+    /// The position of the vertex in clip space.
+    pub position: [f32; 3],
+    /// The texture coordinates of the vertex.
+    pub tex_coord: [f32; 2],
+}
+
+impl attrib::VertexAttributes for Vertex {
+    fn attrib_info() -> crate::Result<attrib::Info> {
+        let mut info = attrib::Info::new();
+        info.add_loader(Register::new(0)?, Format::Float, <[f32; 3]>::COUNT)?;
+        info.add_loader(Register::new(1)?, Format::Float, <[f32; 2]>::COUNT)?;
+        Ok(info)
+    }
+}
+
+/// A single triangle which covers the whole screen, for use with the
+/// [`PASSTHROUGH`] shader in post-processing effects. Texture coordinates
+/// range from `(0, 0)` at the bottom left of the screen to `(1, 1)` at the top
+/// right, accounting for the rotation of the 3DS screens.
+pub const FULLSCREEN_TRIANGLE: [Vertex; 3] = [
+    // The clip-space X axis points up the screen, and Y points left.
+    Vertex {
+        position: [-1.0, -1.0, -0.5],
+        tex_coord: [1.0, 0.0],
+    },
+    Vertex {
+        position: [3.0, -1.0, -0.5],
+        tex_coord: [1.0, 2.0],
+    },
+    Vertex {
+        position: [-1.0, 3.0, -0.5],
+        tex_coord: [-1.0, 0.0],
+    },
+];
+
+/// A vertex shader for [`ExpandVertex`] data, which transforms positions into
+/// view space by the `modelView` uniform and passes them on to the
+/// [`POINT_SPRITE`] or [`WIDE_LINE`] geometry shader.
+pub static EXPAND_VERTEX: &[u8] = citro3d_macros::include_shader!("builtin/expand.pica");
+
+/// A geometry shader which expands each point into a quad facing the camera,
+/// e.g. for particles. The quad is [`ExpandVertex::size`] wide and tall, with
+/// texture coordinates from `(0, 0)` to `(1, 1)`, and is projected by the
+/// `projection` uniform.
+///
+/// Use it with [`EXPAND_VERTEX`] and [`POINT_SPRITE_INPUT`], and draw one
+/// vertex per point using [`Primitive::GeometryPrim`](crate::buffer::Primitive::GeometryPrim).
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::shader::{self, builtin};
+/// let vertex = shader::Library::from_bytes(builtin::EXPAND_VERTEX).unwrap();
+/// let geometry = shader::Library::from_bytes(builtin::POINT_SPRITE).unwrap();
+///
+/// let mut program = shader::Program::new(vertex.get(0).unwrap()).unwrap();
+/// program
+///     .set_geometry_shader_with(geometry.get(0).unwrap(), builtin::POINT_SPRITE_INPUT)
+///     .unwrap();
+/// ```
+pub static POINT_SPRITE: &[u8] = citro3d_macros::include_shader!("builtin/point_sprite.pica");
+
+/// A geometry shader which expands each line into a quad facing the camera,
+/// e.g. for debug drawing. The quad is [`ExpandVertex::size`] wide at each
+/// end, with texture coordinates running from `0` to `1` along the line (U)
+/// and across it (V), and is projected by the `projection` uniform.
+///
+/// Use it with [`EXPAND_VERTEX`] and [`WIDE_LINE_INPUT`], and draw two
+/// vertices per line using [`Primitive::GeometryPrim`](crate::buffer::Primitive::GeometryPrim).
+/// Lines of zero length are not drawn.
+pub static WIDE_LINE: &[u8] = citro3d_macros::include_shader!("builtin/wide_line.pica");
+
+/// The geometry shader input layout for [`POINT_SPRITE`]: one
+/// [`ExpandVertex`] per point.
+pub const POINT_SPRITE_INPUT: GeometryInput = GeometryInput {
+    stride: 3,
+    permutation: GeometryInput::IDENTITY,
+};
+
+/// The geometry shader input layout for [`WIDE_LINE`]: two [`ExpandVertex`]
+/// per line.
+pub const WIDE_LINE_INPUT: GeometryInput = GeometryInput {
+    stride: 6,
+    permutation: GeometryInput::IDENTITY,
+};
+
+/// A vertex for the [`EXPAND_VERTEX`] shader.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExpandVertex {
+    /// The position of the vertex in model space.
+    pub position: [f32; 3],
+    /// The color of the vertex.
+    pub color: [f32; 4],
+    /// The side length of a point sprite, or the width of a line at this
+    /// vertex, in view space units.
+    pub size: f32,
+}
+
+impl attrib::VertexAttributes for ExpandVertex {
+    fn attrib_info() -> crate::Result<attrib::Info> {
+        let mut info = attrib::Info::new();
+        info.add_loader(Register::new(0)?, Format::Float, <[f32; 3]>::COUNT)?;
+        info.add_loader(Register::new(1)?, Format::Float, <[f32; 4]>::COUNT)?;
+        info.add_loader(Register::new(2)?, Format::Float, f32::COUNT)?;
+        Ok(info)
+    }
+}
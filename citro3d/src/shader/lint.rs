@@ -0,0 +1,156 @@
+//! Checks for common mistakes in shaders, which are otherwise easy to miss
+//! since they tend to result in nothing being drawn rather than an error.
+//!
+//! Use [`Library::from_bytes_checked`] to run [`check`] when a shader is
+//! loaded, e.g. to report the warnings in debug builds.
+
+use std::fmt;
+
+use super::{Library, OutputKind, Program, Type};
+
+/// A potential problem found in a shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// An output register is declared, but none of its components are used.
+    UnusedOutput {
+        /// The index of the entrypoint declaring the output.
+        entrypoint: usize,
+        /// The output register.
+        register: u8,
+    },
+    /// A vertex shader writes a [`Dummy`](OutputKind::Dummy) output, but the
+    /// library has no geometry shader that could read it.
+    DummyOutput {
+        /// The index of the entrypoint declaring the output.
+        entrypoint: usize,
+        /// The output register.
+        register: u8,
+    },
+    /// A vertex shader doesn't output a position, so nothing it processes
+    /// will be drawn.
+    MissingPosition {
+        /// The index of the vertex shader entrypoint.
+        entrypoint: usize,
+    },
+    /// A uniform which was expected (e.g. by helper APIs used with the shader)
+    /// is not declared by any entrypoint.
+    MissingUniform {
+        /// The name of the expected uniform.
+        name: String,
+    },
+    /// A program has a geometry shader, but its input stride is zero, so the
+    /// geometry shader won't receive any vertex attributes.
+    GeometryWithoutStride,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnusedOutput {
+                entrypoint,
+                register,
+            } => write!(
+                f,
+                "entrypoint {entrypoint}: output o{register} has no components in use"
+            ),
+            Self::DummyOutput {
+                entrypoint,
+                register,
+            } => write!(
+                f,
+                "entrypoint {entrypoint}: dummy output o{register} is never read \
+                 without a geometry shader"
+            ),
+            Self::MissingPosition { entrypoint } => write!(
+                f,
+                "entrypoint {entrypoint}: vertex shader does not output a position"
+            ),
+            Self::MissingUniform { name } => write!(f, "uniform `{name}` is not declared"),
+            Self::GeometryWithoutStride => {
+                write!(f, "geometry shader is set with an input stride of zero")
+            }
+        }
+    }
+}
+
+/// Check a shader library for common mistakes. Each name in
+/// `expected_uniforms` should be declared as a uniform by at least one of the
+/// library's entrypoints.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::macros::include_shader;
+/// # use citro3d::shader::{self, lint};
+/// # static SHADER_BYTES: &[u8] = include_shader!("../examples/assets/vshader.pica");
+/// let library = shader::Library::from_bytes(SHADER_BYTES).unwrap();
+///
+/// let warnings = lint::check(&library, &["projection", "modelView"]);
+/// assert_eq!(
+///     warnings,
+///     [lint::Warning::MissingUniform {
+///         name: String::from("modelView")
+///     }]
+/// );
+/// ```
+pub fn check(library: &Library, expected_uniforms: &[&str]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let has_geometry_shader = library
+        .entrypoints()
+        .any(|entry| entry.ty() == Type::Geometry);
+
+    for (entrypoint, entry) in library.entrypoints().enumerate() {
+        let mut has_position = false;
+
+        for output in entry.outputs() {
+            has_position |= output.kind == OutputKind::Position;
+
+            if output.mask == 0 {
+                warnings.push(Warning::UnusedOutput {
+                    entrypoint,
+                    register: output.register,
+                });
+            } else if output.kind == OutputKind::Dummy
+                && entry.ty() == Type::Vertex
+                && !has_geometry_shader
+            {
+                warnings.push(Warning::DummyOutput {
+                    entrypoint,
+                    register: output.register,
+                });
+            }
+        }
+
+        if entry.ty() == Type::Vertex && !has_geometry_shader && !has_position {
+            warnings.push(Warning::MissingPosition { entrypoint });
+        }
+    }
+
+    for &name in expected_uniforms {
+        let declared = library
+            .entrypoints()
+            .any(|entry| entry.uniforms().any(|uniform| uniform.name == name));
+
+        if !declared {
+            warnings.push(Warning::MissingUniform {
+                name: name.to_string(),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Check a shader program for common mistakes in how it was set up.
+pub fn check_program(program: &Program) -> Vec<Warning> {
+    let raw = &program.program;
+    let mut warnings = Vec::new();
+
+    if !raw.geometryShader.is_null() && raw.geoShaderInputStride == 0 {
+        warnings.push(Warning::GeometryWithoutStride);
+    }
+
+    warnings
+}
@@ -0,0 +1,95 @@
+//! Per-frame rendering statistics, to help find out what is taking up time on
+//! the GPU.
+//!
+//! The GPU timings are measured by `citro3d` itself, while the counts are
+//! maintained by the wrappers in this crate. Calls made directly through
+//! `citro3d-sys` are not counted.
+
+use crate::buffer::Primitive;
+
+/// Statistics for the most recently completed frame. See
+/// [`Instance::stats`](crate::Instance::stats).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// Time the GPU spent processing the command buffer, in milliseconds.
+    pub processing_time: f32,
+    /// Time the GPU spent drawing, in milliseconds.
+    pub drawing_time: f32,
+    /// The fraction (from `0.0` to `1.0`) of the command buffer in use.
+    pub cmdbuf_usage: f32,
+    /// The number of draw calls, both from vertex buffers and immediate mode.
+    pub draw_calls: usize,
+    /// The number of triangles submitted by draw calls. Primitives produced by
+    /// a geometry shader are not counted.
+    pub triangles: usize,
+    /// The number of times the shader program, attribute info, buffer info or
+    /// render target was changed.
+    pub state_changes: usize,
+    /// The number of [`TexEnv`](crate::texenv::TexEnv) stages sent to the
+    /// GPU, or combiner buffer settings changed. Stages which didn't change
+    /// since the last draw call aren't counted.
+    pub texenv_updates: usize,
+}
+
+/// Running counts for the frame currently being rendered.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Counters {
+    pub draw_calls: usize,
+    pub triangles: usize,
+    pub state_changes: usize,
+    pub texenv_updates: usize,
+}
+
+impl Counters {
+    pub fn record_draw(&mut self, primitive: Primitive, vertices: usize) {
+        self.draw_calls += 1;
+        self.triangles += triangle_count(primitive, vertices);
+    }
+}
+
+/// Get the number of triangles drawn by `vertices` vertices of the given
+/// primitive type.
+fn triangle_count(primitive: Primitive, vertices: usize) -> usize {
+    match primitive {
+        Primitive::Triangles => vertices / 3,
+        Primitive::TriangleStrip | Primitive::TriangleFan => vertices.saturating_sub(2),
+        Primitive::GeometryPrim => 0,
+    }
+}
+
+impl Stats {
+    pub(crate) fn new(counters: Counters) -> Self {
+        let Counters {
+            draw_calls,
+            triangles,
+            state_changes,
+            texenv_updates,
+        } = counters;
+
+        unsafe {
+            Self {
+                processing_time: citro3d_sys::C3D_GetProcessingTime(),
+                drawing_time: citro3d_sys::C3D_GetDrawingTime(),
+                cmdbuf_usage: citro3d_sys::C3D_GetCmdBufUsage(),
+                draw_calls,
+                triangles,
+                state_changes,
+                texenv_updates,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triangles_per_primitive() {
+        assert_eq!(triangle_count(Primitive::Triangles, 7), 2);
+        assert_eq!(triangle_count(Primitive::TriangleStrip, 6), 4);
+        assert_eq!(triangle_count(Primitive::TriangleFan, 1), 0);
+        assert_eq!(triangle_count(Primitive::GeometryPrim, 9), 0);
+    }
+}
@@ -0,0 +1,147 @@
+//! Conversion of linear pixel data into the tiled layout expected by the GPU.
+//!
+//! The PICA200 does not sample textures stored row by row. Instead, textures
+//! are split into 8x8 tiles stored left to right and bottom to top, and the
+//! pixels within each tile are stored in
+//! [Morton (Z-order)](https://en.wikipedia.org/wiki/Z-order_curve) order.
+//! Images decoded by most libraries (e.g. the `image` crate) are stored
+//! linearly from top to bottom, and must be swizzled before uploading them.
+//!
+//! See <https://www.3dbrew.org/wiki/GPU/Textures> for details of the format.
+
+/// The width and height of a tile, in pixels.
+const TILE_SIZE: usize = 8;
+
+/// Get the offset of a pixel within its 8x8 tile, by interleaving the bits of
+/// its coordinates (`x` in the least significant bit).
+fn morton_offset(x: usize, y: usize) -> usize {
+    let mut offset = 0;
+    for bit in 0..3 {
+        offset |= ((x >> bit) & 1) << (2 * bit);
+        offset |= ((y >> bit) & 1) << (2 * bit + 1);
+    }
+    offset
+}
+
+/// Swizzle the linear, top-to-bottom image in `src` into `dst`, with each
+/// pixel taking up `bytes_per_pixel` bytes. The bytes within each pixel are
+/// copied unchanged; see [`tile_rgba8`] to also convert the channel order.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidSize`](crate::Error::InvalidSize) if the image is
+/// empty, `width` or `height` is not a multiple of 8, or either buffer is not exactly
+/// `width * height * bytes_per_pixel` bytes long.
+pub fn tile_into(
+    src: &[u8],
+    dst: &mut [u8],
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+) -> crate::Result<()> {
+    let size = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(bytes_per_pixel))
+        .ok_or(crate::Error::InvalidSize)?;
+
+    if size == 0
+        || width % TILE_SIZE != 0
+        || height % TILE_SIZE != 0
+        || src.len() != size
+        || dst.len() != size
+    {
+        return Err(crate::Error::InvalidSize);
+    }
+
+    let tiles_per_row = width / TILE_SIZE;
+
+    for (row, src_row) in src.chunks_exact(width * bytes_per_pixel).enumerate() {
+        // The first tile is at the bottom of the image
+        let y = height - 1 - row;
+        for (x, pixel) in src_row.chunks_exact(bytes_per_pixel).enumerate() {
+            let tile = (y / TILE_SIZE) * tiles_per_row + x / TILE_SIZE;
+            let index = tile * TILE_SIZE * TILE_SIZE + morton_offset(x % TILE_SIZE, y % TILE_SIZE);
+            let offset = index * bytes_per_pixel;
+            dst[offset..offset + bytes_per_pixel].copy_from_slice(pixel);
+        }
+    }
+
+    Ok(())
+}
+
+/// Swizzle a linear, top-to-bottom image into a newly allocated buffer. See
+/// [`tile_into`] for details.
+///
+/// # Errors
+///
+/// Fails under the same conditions as [`tile_into`].
+pub fn tile(
+    src: &[u8],
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+) -> crate::Result<Vec<u8>> {
+    let mut dst = vec![0; src.len()];
+    tile_into(src, &mut dst, width, height, bytes_per_pixel)?;
+    Ok(dst)
+}
+
+/// Swizzle a linear, top-to-bottom image with 8-bit RGBA pixels (as produced
+/// by e.g. `image::RgbaImage`) for use as an RGBA8 texture. Besides tiling,
+/// this reverses the bytes of each pixel, since the GPU stores RGBA8 texels
+/// as little-endian `0xRRGGBBAA` words.
+///
+/// # Errors
+///
+/// Fails under the same conditions as [`tile_into`].
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::swizzle;
+/// let pixels = [0xff, 0x00, 0x00, 0x80].repeat(8 * 8);
+/// let tiled = swizzle::tile_rgba8(&pixels, 8, 8).unwrap();
+/// assert_eq!(tiled[..4], [0x80, 0x00, 0x00, 0xff]);
+/// ```
+pub fn tile_rgba8(src: &[u8], width: usize, height: usize) -> crate::Result<Vec<u8>> {
+    let mut dst = tile(src, width, height, 4)?;
+    for pixel in dst.chunks_exact_mut(4) {
+        pixel.reverse();
+    }
+    Ok(dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn morton_order_within_tile() {
+        assert_eq!(morton_offset(0, 0), 0);
+        assert_eq!(morton_offset(1, 0), 1);
+        assert_eq!(morton_offset(0, 1), 2);
+        assert_eq!(morton_offset(1, 1), 3);
+        assert_eq!(morton_offset(2, 0), 4);
+        assert_eq!(morton_offset(7, 7), 63);
+    }
+
+    #[test]
+    fn tiles_start_at_bottom_left() {
+        // 16x8 image where each pixel holds its linear index
+        let src: Vec<u8> = (0..128).collect();
+        let dst = tile(&src, 16, 8, 1).unwrap();
+
+        // Bottom-left pixel of the first tile, then its right neighbour and
+        // the pixel above it
+        assert_eq!(dst[..3], [112, 113, 96]);
+        // The second tile starts at the bottom of the right half
+        assert_eq!(dst[64], 120);
+    }
+
+    #[test]
+    fn tile_rejects_bad_sizes() {
+        assert!(tile(&[0; 12 * 8], 12, 8, 1).is_err());
+        assert!(tile(&[0; 10], 8, 8, 1).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+//! A native parser for the Tex3DS (`.t3x`) texture container, as produced by
+//! the `tex3ds` tool from devkitPro.
+//!
+//! This reads the container's metadata (texture size, format, and the atlas
+//! regions of each sub-texture) from any [`Read`] source, without needing the
+//! whole file in memory. The texture data itself follows the metadata, and is
+//! compressed using one of the formats supported by `libctru`'s `decompress`.
+
+use std::io::{self, Read};
+
+/// The metadata of a Tex3DS texture.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::tex3ds::Tex3ds;
+/// // A 64x32 texture with one sub-texture covering the whole atlas
+/// let bytes: &[u8] = &[1, 0, 0b010_011, 0, 0, 64, 0, 32, 0, 0, 0, 0, 4, 0, 4, 0, 0];
+///
+/// let t3x = Tex3ds::read(bytes).unwrap();
+/// assert_eq!((t3x.width(), t3x.height()), (64, 32));
+/// assert_eq!(t3x.sub_textures()[0].right, 1.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[doc(alias = "Tex3DS_Texture")]
+pub struct Tex3ds {
+    width_log2: u8,
+    height_log2: u8,
+    is_cube_map: bool,
+    format: u8,
+    mipmap_levels: u8,
+    sub_textures: Vec<SubTexture>,
+}
+
+/// A region of a Tex3DS texture atlas, with texture coordinates from `0.0` to
+/// `1.0` measured from the bottom-left of the texture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[doc(alias = "Tex3DS_SubTexture")]
+pub struct SubTexture {
+    /// The width of the sub-texture, in pixels.
+    pub width: u16,
+    /// The height of the sub-texture, in pixels.
+    pub height: u16,
+    /// The U coordinate of the sub-texture's left edge.
+    pub left: f32,
+    /// The V coordinate of the sub-texture's top edge.
+    pub top: f32,
+    /// The U coordinate of the sub-texture's right edge.
+    pub right: f32,
+    /// The V coordinate of the sub-texture's bottom edge.
+    pub bottom: f32,
+}
+
+impl SubTexture {
+    /// Whether the sub-texture is stored rotated 90 degrees in the atlas, in
+    /// which case `top` is less than `bottom`.
+    #[doc(alias = "Tex3DS_SubTextureRotated")]
+    pub fn is_rotated(&self) -> bool {
+        self.top < self.bottom
+    }
+
+    /// Move each edge of the sub-texture half a texel inward, given the size
+    /// in pixels of the atlas it belongs to. Sampling with the inset
+    /// coordinates keeps linear filtering from blending in texels of
+    /// neighbouring sub-textures at the edges.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::tex3ds::SubTexture;
+    /// let sprite = SubTexture {
+    ///     width: 16,
+    ///     height: 16,
+    ///     left: 0.0,
+    ///     top: 1.0,
+    ///     right: 0.25,
+    ///     bottom: 0.75,
+    /// };
+    ///
+    /// let inset = sprite.inset_uvs(64, 64);
+    /// assert_eq!((inset.left, inset.top), (0.5 / 64.0, 1.0 - 0.5 / 64.0));
+    /// ```
+    pub fn inset_uvs(&self, atlas_width: u16, atlas_height: u16) -> Self {
+        let half_u = 0.5 / f32::from(atlas_width);
+        let half_v = 0.5 / f32::from(atlas_height);
+
+        // Rotated sub-textures have their edges in the opposite order, so move
+        // each edge towards its opposite rather than in a fixed direction
+        let inset = |from: f32, to: f32, half: f32| {
+            if from <= to {
+                (from + half, to - half)
+            } else {
+                (from - half, to + half)
+            }
+        };
+
+        let (left, right) = inset(self.left, self.right, half_u);
+        let (top, bottom) = inset(self.top, self.bottom, half_v);
+
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+            ..*self
+        }
+    }
+}
+
+impl Tex3ds {
+    /// Read the metadata of a Tex3DS texture. When this returns successfully,
+    /// `reader` is positioned at the start of the (compressed) texture data.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from reading, including
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) if the input is too
+    /// short, and [`InvalidData`](io::ErrorKind::InvalidData) if the header
+    /// is malformed.
+    #[doc(alias = "Tex3DS_TextureImport")]
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+
+        let [count_lo, count_hi, dimensions, format, mipmap_levels] = header;
+        let count = u16::from_le_bytes([count_lo, count_hi]);
+        if dimensions & 0x80 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unknown flags in Tex3DS header",
+            ));
+        }
+
+        let mut sub_textures = Vec::with_capacity(count.into());
+        for _ in 0..count {
+            let mut fields = [0; 12];
+            reader.read_exact(&mut fields)?;
+
+            let field = |i: usize| u16::from_le_bytes([fields[2 * i], fields[2 * i + 1]]);
+            // Texture coordinates are stored as fixed-point with 10 fractional bits
+            let coord = |i: usize| f32::from(field(i)) / 1024.0;
+
+            sub_textures.push(SubTexture {
+                width: field(0),
+                height: field(1),
+                left: coord(2),
+                top: coord(3),
+                right: coord(4),
+                bottom: coord(5),
+            });
+        }
+
+        Ok(Self {
+            width_log2: dimensions & 0x7,
+            height_log2: (dimensions >> 3) & 0x7,
+            is_cube_map: dimensions & 0x40 != 0,
+            format,
+            mipmap_levels,
+            sub_textures,
+        })
+    }
+
+    /// The width of the texture, in pixels.
+    pub fn width(&self) -> u16 {
+        1 << (self.width_log2 + 3)
+    }
+
+    /// The height of the texture, in pixels.
+    pub fn height(&self) -> u16 {
+        1 << (self.height_log2 + 3)
+    }
+
+    /// Whether the texture is a cube map, in which case the data contains six
+    /// faces instead of a single image.
+    pub fn is_cube_map(&self) -> bool {
+        self.is_cube_map
+    }
+
+    /// The color format of the texture data, as a raw `GPU_TEXCOLOR` value.
+    #[doc(alias = "GPU_TEXCOLOR")]
+    pub fn format(&self) -> u8 {
+        self.format
+    }
+
+    /// The number of mipmap levels stored in addition to the full-size image.
+    pub fn mipmap_levels(&self) -> u8 {
+        self.mipmap_levels
+    }
+
+    /// The regions of the texture atlas.
+    #[doc(alias = "Tex3DS_GetSubTexture")]
+    pub fn sub_textures(&self) -> &[SubTexture] {
+        &self.sub_textures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_rotated_sub_texture() {
+        let bytes: &[u8] = &[
+            2, 0, 0b100_111, 0x12, 3, // header
+            8, 0, 8, 0, 0, 0, 0, 4, 0, 2, 0, 2, // first sub-texture
+            16, 0, 4, 0, 0, 2, 0, 1, 0, 4, 0, 2,    // second, rotated
+            0xaa, // start of texture data
+        ];
+        let mut reader = bytes;
+
+        let t3x = Tex3ds::read(&mut reader).unwrap();
+        assert_eq!((t3x.width(), t3x.height()), (1024, 128));
+        assert_eq!((t3x.format(), t3x.mipmap_levels()), (0x12, 3));
+        assert!(!t3x.is_cube_map());
+
+        let [first, second] = t3x.sub_textures() else {
+            panic!("expected two sub-textures");
+        };
+        assert!(!first.is_rotated());
+        assert_eq!((first.right, first.bottom), (0.5, 0.5));
+        assert!(second.is_rotated());
+        assert_eq!((second.left, second.top), (0.5, 0.25));
+
+        assert_eq!(reader, [0xaa]);
+    }
+
+    #[test]
+    fn inset_keeps_rotation() {
+        let rotated = SubTexture {
+            width: 8,
+            height: 8,
+            left: 0.5,
+            top: 0.0,
+            right: 1.0,
+            bottom: 0.5,
+        };
+
+        let inset = rotated.inset_uvs(16, 32);
+        assert!(inset.is_rotated());
+        assert_eq!((inset.left, inset.right), (0.53125, 0.96875));
+        assert_eq!((inset.top, inset.bottom), (0.015625, 0.484375));
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let err = Tex3ds::read(&[1, 0, 0, 0, 0, 8, 0][..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,19 +1,33 @@
 //! Texture combiner support. See <https://www.khronos.org/opengl/wiki/Texture_Combiners>
 //! for more details.
 
+use std::cell::OnceCell;
+
 use bitflags::bitflags;
 
+use crate::color::Color;
+
 /// A texture combiner, also called a "texture environment" (hence the struct name).
 /// See also [`texenv.h` documentation](https://oreo639.github.io/citro3d/texenv_8h.html).
+///
+/// Changes to a combiner are sent to the GPU at the next draw call, and only
+/// if its configuration differs from the one last sent for that stage.
 #[doc(alias = "C3D_TexEnv")]
-pub struct TexEnv(*mut citro3d_sys::C3D_TexEnv);
+#[derive(Clone)]
+pub struct TexEnv {
+    pub(crate) raw: citro3d_sys::C3D_TexEnv,
+}
 
 // https://oreo639.github.io/citro3d/texenv_8h.html#a9eda91f8e7252c91f873b1d43e3728b6
 pub(crate) const TEXENV_COUNT: usize = 6;
 
 impl TexEnv {
-    pub(crate) fn new(stage: Stage) -> Self {
-        let mut result = unsafe { Self(citro3d_sys::C3D_GetTexEnv(stage.0 as _)) };
+    pub(crate) fn new() -> Self {
+        // SAFETY: the struct is plain data, so all zeroes is a valid value,
+        // and it's fully initialized by `reset` anyway
+        let mut result = Self {
+            raw: unsafe { std::mem::zeroed() },
+        };
         result.reset();
         result
     }
@@ -21,7 +35,18 @@ impl TexEnv {
     /// Re-initialize the texture combiner to its default state.
     pub fn reset(&mut self) {
         unsafe {
-            citro3d_sys::C3D_TexEnvInit(self.0);
+            citro3d_sys::C3D_TexEnvInit(&mut self.raw);
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        // SAFETY: C3D_TexEnv is made up of 16- and 32-bit integers laid out
+        // without padding, so every byte is initialized
+        unsafe {
+            std::slice::from_raw_parts(
+                (&self.raw as *const citro3d_sys::C3D_TexEnv).cast(),
+                std::mem::size_of::<citro3d_sys::C3D_TexEnv>(),
+            )
         }
     }
 
@@ -42,7 +67,7 @@ impl TexEnv {
     ) -> &mut Self {
         unsafe {
             citro3d_sys::C3D_TexEnvSrc(
-                self.0,
+                &mut self.raw,
                 mode.bits(),
                 source0 as _,
                 source1.unwrap_or(Source::PrimaryColor) as _,
@@ -61,11 +86,161 @@ impl TexEnv {
     #[doc(alias = "C3D_TexEnvFunc")]
     pub fn func(&mut self, mode: Mode, func: CombineFunc) -> &mut Self {
         unsafe {
-            citro3d_sys::C3D_TexEnvFunc(self.0, mode.bits(), func as _);
+            citro3d_sys::C3D_TexEnvFunc(&mut self.raw, mode.bits(), func as _);
+        }
+
+        self
+    }
+
+    /// Set the constant color used by [`Source::Constant`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::color::Color;
+    /// # use citro3d::texenv::{self, CombineFunc, Mode, Source};
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// // Tint the vertex color red
+    /// instance
+    ///     .texenv(texenv::Stage::new(0).unwrap())
+    ///     .src(
+    ///         Mode::BOTH,
+    ///         Source::PrimaryColor,
+    ///         Some(Source::Constant),
+    ///         None,
+    ///     )
+    ///     .func(Mode::BOTH, CombineFunc::Modulate)
+    ///     .color(Color::rgb(0xFF, 0x40, 0x40));
+    /// ```
+    #[doc(alias = "C3D_TexEnvColor")]
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        unsafe {
+            citro3d_sys::C3D_TexEnvColor(&mut self.raw, color.to_gpu());
+        }
+
+        self
+    }
+
+    /// Configure the scale applied to the output of the combination function.
+    /// The scaled result is clamped to the range `0.0..=1.0`, so any
+    /// precision lost before scaling can't be recovered.
+    ///
+    /// # Parameters
+    ///
+    /// - `mode`: the [`Mode`]\(s) the scale will apply to.
+    /// - `scale`: the [`Scale`] to multiply the output by.
+    #[doc(alias = "C3D_TexEnvScale")]
+    pub fn scale(&mut self, mode: Mode, scale: Scale) -> &mut Self {
+        unsafe {
+            citro3d_sys::C3D_TexEnvScale(&mut self.raw, mode.bits() as _, scale as _);
+        }
+
+        self
+    }
+
+    /// Multiply the color output of this stage by the given [`Scale`], leaving
+    /// the alpha output unchanged.
+    ///
+    /// This is commonly needed to restore brightness after combination
+    /// functions that produce darker results than expected, such as
+    /// [`CombineFunc::Dot3Rgb`] (where a "fully lit" surface often comes out
+    /// at around half brightness) or [`CombineFunc::Modulate`] with lighting.
+    /// Since the result is clamped, scaling an already bright color will
+    /// saturate to white.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::texenv::{self, CombineFunc, Mode, Scale, Source};
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let stage = texenv::Stage::new(0).unwrap();
+    /// instance
+    ///     .texenv(stage)
+    ///     .src(
+    ///         Mode::RGB,
+    ///         Source::FragmentPrimaryColor,
+    ///         Some(Source::PrimaryColor),
+    ///         None,
+    ///     )
+    ///     .func(Mode::RGB, CombineFunc::Modulate)
+    ///     .brighten(Scale::X2);
+    /// ```
+    pub fn brighten(&mut self, scale: Scale) -> &mut Self {
+        self.scale(Mode::RGB, scale)
+    }
+}
+
+impl PartialEq for TexEnv {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for TexEnv {}
+
+/// The texture combiners used for subsequent draw calls, as configured with
+/// [`Instance::set_texenvs`](crate::Instance::set_texenvs).
+///
+/// Any stage which isn't configured through [`stage`](Self::stage) is reset to
+/// pass through the previous stage's output unchanged, unless
+/// [`keep_unused`](Self::keep_unused) is called.
+pub struct TexEnvs<'instance> {
+    texenvs: &'instance mut [OnceCell<TexEnv>; TEXENV_COUNT],
+    configured: [bool; TEXENV_COUNT],
+    keep_unused: bool,
+}
+
+impl<'instance> TexEnvs<'instance> {
+    pub(crate) fn new(texenvs: &'instance mut [OnceCell<TexEnv>; TEXENV_COUNT]) -> Self {
+        Self {
+            texenvs,
+            configured: [false; TEXENV_COUNT],
+            keep_unused: false,
         }
+    }
+
+    /// Get the texture combiner for `stage`, marking it as in use.
+    pub fn stage(&mut self, stage: Stage) -> &mut TexEnv {
+        self.configured[stage.0] = true;
+        get_or_init(&mut self.texenvs[stage.0])
+    }
 
+    /// Leave stages which weren't configured in whatever state they were in
+    /// before, instead of resetting them.
+    pub fn keep_unused(&mut self) -> &mut Self {
+        self.keep_unused = true;
         self
     }
+
+    /// Reset the unused stages, unless opted out.
+    pub(crate) fn finish(self) {
+        if self.keep_unused {
+            return;
+        }
+
+        for (texenv, configured) in self.texenvs.iter_mut().zip(self.configured) {
+            if !configured {
+                // Newly initialized stages are already reset
+                match texenv.get_mut() {
+                    Some(texenv) => texenv.reset(),
+                    None => {
+                        get_or_init(texenv);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Get the texture combiner from its cell, initializing it if this is the
+/// first time it's been used.
+pub(crate) fn get_or_init(texenv: &mut OnceCell<TexEnv>) -> &mut TexEnv {
+    texenv.get_or_init(TexEnv::new);
+    // We have to do this weird unwrap to get a mutable reference,
+    // since there is no `get_mut_or_init` or equivalent
+    texenv.get_mut().unwrap()
 }
 
 bitflags! {
@@ -81,6 +256,23 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// The texture combiner stages whose output is written to the combiner
+    /// buffer, which later stages can read as [`Source::PreviousBuffer`]. Only
+    /// the first four stages can write to the buffer.
+    #[doc(alias = "C3D_TexEnvBufUpdate")]
+    pub struct BufferWrites: u8 {
+        #[allow(missing_docs)]
+        const STAGE0 = 1 << 0;
+        #[allow(missing_docs)]
+        const STAGE1 = 1 << 1;
+        #[allow(missing_docs)]
+        const STAGE2 = 1 << 2;
+        #[allow(missing_docs)]
+        const STAGE3 = 1 << 3;
+    }
+}
+
 /// A source operand of a [`TexEnv`]'s texture combination.
 #[doc(alias = "GPU_TEVSRC")]
 #[allow(missing_docs)]
@@ -114,8 +306,21 @@ pub enum CombineFunc {
     Interpolate = ctru_sys::GPU_INTERPOLATE,
     Subtract = ctru_sys::GPU_SUBTRACT,
     Dot3Rgb = ctru_sys::GPU_DOT3_RGB,
-    // Added in libcrtu 2.3.0:
-    // Dot3Rgba = ctru_sys::GPU_DOT3_RGBA,
+    Dot3Rgba = ctru_sys::GPU_DOT3_RGBA,
+}
+
+/// The scale applied to the output of a [`TexEnv`]'s combination function.
+#[doc(alias = "GPU_TEVSCALE")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Scale {
+    /// Leave the output unchanged. This is the default.
+    #[default]
+    X1 = ctru_sys::GPU_TEVSCALE_1,
+    /// Multiply the output by 2.
+    X2 = ctru_sys::GPU_TEVSCALE_2,
+    /// Multiply the output by 4.
+    X4 = ctru_sys::GPU_TEVSCALE_4,
 }
 
 /// A texture combination stage identifier. This index doubles as the order
@@ -1,7 +1,9 @@
 //! Common definitions for binding uniforms to shaders. This is primarily
-//! done by implementing the [`Uniform`] trait for a given type.
+//! done by converting values into a [`Uniform`], e.g. by implementing the
+//! [`IntoUniform`] trait for a given type.
 
 use std::ops::Range;
+use std::ptr::addr_of;
 
 use crate::math::{FVec4, IVec, Matrix4};
 use crate::{shader, Instance};
@@ -22,6 +24,172 @@ impl From<Index> for i32 {
     }
 }
 
+impl Index {
+    /// Get the index `n` registers after this one, e.g. for a row of a matrix
+    /// uniform. Returns `None` if the result would be outside the float,
+    /// integer, or boolean registers this index is in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::uniform::Index;
+    /// let matrix = Index::from(4);
+    /// assert_eq!(matrix.offset(3), Some(Index::from(7)));
+    /// ```
+    pub fn offset(self, n: u8) -> Option<Self> {
+        let index = self.0.checked_add(n)?;
+        Kind::of(self)
+            .registers()
+            .contains(&index)
+            .then_some(Self(index))
+    }
+}
+
+/// A range of consecutive uniform registers, such as the ones a shader reserves
+/// for a single uniform (see [`shader::Program::get_uniform_range`]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IndexRange {
+    start: Index,
+    len: u8,
+}
+
+impl IndexRange {
+    /// Create a range of `len` registers beginning at `start`. Returns `None`
+    /// if the range would extend past the last of the float, integer, or
+    /// boolean registers `start` is in.
+    pub fn new(start: Index, len: u8) -> Option<Self> {
+        let registers = Kind::of(start).registers();
+        let end = start.0.checked_add(len)?;
+        (registers.contains(&start.0) && end <= registers.end).then_some(Self { start, len })
+    }
+
+    /// The first register in the range.
+    pub fn start(&self) -> Index {
+        self.start
+    }
+
+    /// The register just past the end of the range.
+    pub fn end(&self) -> Index {
+        Index(self.start.0 + self.len)
+    }
+
+    /// The number of registers in the range.
+    #[allow(clippy::len_without_is_empty)] // uniforms always use at least one register
+    pub fn len(&self) -> usize {
+        self.len.into()
+    }
+
+    /// Whether the given register is within the range.
+    pub fn contains(&self, index: Index) -> bool {
+        (self.start..self.end()).contains(&index)
+    }
+
+    /// Get the `n`th register of the range, if it is within the range.
+    pub fn get(&self, n: u8) -> Option<Index> {
+        (n < self.len).then(|| Index(self.start.0 + n))
+    }
+
+    /// Iterate over each register in the range.
+    pub fn iter(&self) -> impl Iterator<Item = Index> {
+        (self.start.0..self.end().0).map(Index)
+    }
+
+    /// Whether the given uniform can be bound to this range, i.e. it uses the
+    /// right kind of registers and does not write past the end of the range.
+    pub fn fits(&self, uniform: &Uniform) -> bool {
+        uniform.kind() == Kind::of(self.start) && uniform.len() <= self.len()
+    }
+}
+
+/// The index of a boolean uniform (`.bool name`), numbered separately from
+/// other uniforms. Valid indices range from 0 to 15.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BoolIndex(u8);
+
+impl BoolIndex {
+    /// Get a boolean uniform index, if it is valid.
+    pub fn new(index: u8) -> Option<Self> {
+        (index < 16).then_some(Self(index))
+    }
+}
+
+impl TryFrom<Index> for BoolIndex {
+    type Error = crate::Error;
+
+    /// Convert an [`Index`], e.g. from [`shader::Program::get_uniform`], into
+    /// the index of the boolean register it refers to.
+    fn try_from(value: Index) -> Result<Self, Self::Error> {
+        let range = Uniform::Bool(false).index_range();
+        if range.contains(&value) {
+            Ok(Self(value.0 - range.start.0))
+        } else {
+            Err(crate::Error::NotFound)
+        }
+    }
+}
+
+/// The index of an integer uniform (`.ivec name`), numbered separately from
+/// other uniforms. Valid indices range from 0 to 3.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IntIndex(u8);
+
+impl IntIndex {
+    /// Get an integer uniform index, if it is valid.
+    pub fn new(index: u8) -> Option<Self> {
+        (index < 4).then_some(Self(index))
+    }
+}
+
+impl TryFrom<Index> for IntIndex {
+    type Error = crate::Error;
+
+    /// Convert an [`Index`], e.g. from [`shader::Program::get_uniform`], into
+    /// the index of the integer register it refers to.
+    fn try_from(value: Index) -> Result<Self, Self::Error> {
+        let range = Uniform::Int(IVec::new(0, 0, 0, 0)).index_range();
+        if range.contains(&value) {
+            Ok(Self(value.0 - range.start.0))
+        } else {
+            Err(crate::Error::NotFound)
+        }
+    }
+}
+
+/// The kind of registers a uniform is stored in, which determines the
+/// [`Uniform`] variants that can be bound to it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// Float vector registers (`.fvec`), for [`Uniform::Float`] through [`Uniform::Float4`].
+    Float,
+    /// Integer vector registers (`.ivec`), for [`Uniform::Int`].
+    Int,
+    /// Boolean registers (`.bool`), for [`Uniform::Bool`].
+    Bool,
+}
+
+impl Kind {
+    /// Get the kind of uniform stored at the given index.
+    pub fn of(index: Index) -> Self {
+        // See `Uniform::index_range` for where these ranges come from
+        match index.0 {
+            0..=0x5F => Self::Float,
+            0x60..=0x67 => Self::Int,
+            _ => Self::Bool,
+        }
+    }
+
+    /// The registers of this kind, in the same numbering as [`Index`].
+    fn registers(self) -> Range<u8> {
+        match self {
+            Self::Float => 0..0x60,
+            Self::Int => 0x60..0x64,
+            // this gap is intentional
+            Self::Bool => 0x68..0x78,
+        }
+    }
+}
+
 /// A uniform which may be bound as input to a shader program
 #[non_exhaustive]
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -50,15 +218,18 @@ impl Uniform {
     pub fn index_range(&self) -> Range<Index> {
         // these indexes are from the uniform table in the shader see: https://www.3dbrew.org/wiki/SHBIN#Uniform_Table_Entry
         // the input registers then are excluded by libctru, see: https://github.com/devkitPro/libctru/blob/0da8705527f03b4b08ff7fee4dd1b7f28df37905/libctru/source/gpu/shbin.c#L93
+        let registers = self.kind().registers();
+        Index(registers.start)..Index(registers.end)
+    }
+    /// Get the kind of registers this uniform is stored in.
+    pub fn kind(&self) -> Kind {
         match self {
-            Self::Float(_) | Self::Float2(_) | Self::Float3(_) | Self::Float4(_) => {
-                Index(0)..Index(0x60)
-            }
-            Self::Int(_) => Index(0x60)..Index(0x64),
-            // this gap is intentional
-            Self::Bool(_) => Index(0x68)..Index(0x79),
+            Self::Float(_) | Self::Float2(_) | Self::Float3(_) | Self::Float4(_) => Kind::Float,
+            Self::Int(_) => Kind::Int,
+            Self::Bool(_) => Kind::Bool,
         }
     }
+
     /// Get length of uniform, i.e. how many registers it will write to
     #[allow(clippy::len_without_is_empty)] // is_empty doesn't make sense here
     pub fn len(&self) -> usize {
@@ -85,32 +256,19 @@ impl Uniform {
         assert!(self.index_range().end.0 as usize >= self.len() + index.0 as usize, "tried to bind a uniform that would overflow the uniform buffer. index was {:?}, size was {} max is {:?}", index, self.len(), self.index_range().end);
         let set_fvs = |fs: &[FVec4]| {
             for (off, f) in fs.iter().enumerate() {
+                let id = index.0 as usize + off;
+                if float_unchanged(ty, id, *f) {
+                    continue;
+                }
                 unsafe {
-                    citro3d_sys::C3D_FVUnifSet(
-                        ty.into(),
-                        (index.0 as usize + off) as i32,
-                        f.x(),
-                        f.y(),
-                        f.z(),
-                        f.w(),
-                    );
+                    citro3d_sys::C3D_FVUnifSet(ty.into(), id as i32, f.x(), f.y(), f.z(), f.w());
                 }
             }
         };
         match self {
-            Self::Bool(b) => unsafe {
-                citro3d_sys::C3D_BoolUnifSet(ty.into(), index.into(), b);
-            },
-            Self::Int(i) => unsafe {
-                citro3d_sys::C3D_IVUnifSet(
-                    ty.into(),
-                    index.into(),
-                    i.x() as i32,
-                    i.y() as i32,
-                    i.z() as i32,
-                    i.w() as i32,
-                );
-            },
+            // UNWRAP: we already checked the index is in range for these types
+            Self::Bool(b) => bind_bool(ty, BoolIndex::try_from(index).unwrap(), b),
+            Self::Int(i) => bind_int(ty, IntIndex::try_from(index).unwrap(), i),
             Self::Float(f) => set_fvs(&[f]),
             Self::Float2(fs) => {
                 set_fvs(&fs);
@@ -123,11 +281,113 @@ impl Uniform {
     }
 }
 
+/// Bind a boolean uniform. Boolean uniforms have their own register indices,
+/// separate from the [`Index`] used by other uniforms.
+pub(crate) fn bind_bool(ty: shader::Type, index: BoolIndex, value: bool) {
+    // SAFETY: the uniform buffers are only accessed through `Instance`, which
+    // has unique access to them
+    let bools = unsafe { (*addr_of!(citro3d_sys::C3D_BoolUnifs))[usize::from(u8::from(ty))] };
+    if (bools >> index.0) & 1 == u16::from(value) {
+        return;
+    }
+
+    unsafe {
+        citro3d_sys::C3D_BoolUnifSet(ty.into(), index.0.into(), value);
+    }
+}
+
+/// Bind an integer uniform. Integer uniforms have their own register indices,
+/// separate from the [`Index`] used by other uniforms.
+pub(crate) fn bind_int(ty: shader::Type, index: IntIndex, i: IVec) {
+    // SAFETY: see `bind_bool`
+    let current = unsafe {
+        (*addr_of!(citro3d_sys::C3D_IVUnif))[usize::from(u8::from(ty))][usize::from(index.0)]
+    };
+    if current == *i.as_raw() {
+        return;
+    }
+
+    unsafe {
+        citro3d_sys::C3D_IVUnifSet(
+            ty.into(),
+            index.0.into(),
+            i.x() as i32,
+            i.y() as i32,
+            i.z() as i32,
+            i.w() as i32,
+        );
+    }
+}
+
+/// Whether the float register `id` already holds `value`. `citro3d` re-uploads
+/// every register that is set, even to the same value, so skipping these avoids
+/// filling the command buffer with duplicate uploads when e.g. the same
+/// projection matrix is bound for many draw calls.
+fn float_unchanged(ty: shader::Type, id: usize, value: FVec4) -> bool {
+    // SAFETY: see `bind_bool`
+    let current = unsafe { (*addr_of!(citro3d_sys::C3D_FVUnif))[usize::from(u8::from(ty))][id] };
+    // Compare bits rather than values, so e.g. -0.0 still replaces 0.0
+    unsafe { current.c.map(f32::to_bits) == value.0.c.map(f32::to_bits) }
+}
+
+/// A type which can be bound as a float uniform, by converting it into a set of
+/// up to four [`FVec4`] registers. Implementing this trait allows binding
+/// e.g. material or lighting structs directly, since any implementor can be
+/// converted [`Into<Uniform>`](Uniform).
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::math::FVec4;
+/// # use citro3d::uniform::{self, IntoUniform};
+/// #
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// struct Material {
+///     ambient: FVec4,
+///     diffuse: FVec4,
+/// }
+///
+/// impl IntoUniform for Material {
+///     type Registers = [FVec4; 2];
+///
+///     fn into_registers(self) -> Self::Registers {
+///         [self.ambient, self.diffuse]
+///     }
+/// }
+///
+/// let material = Material {
+///     ambient: FVec4::splat(0.2),
+///     diffuse: FVec4::new(1.0, 0.0, 0.0, 1.0),
+/// };
+/// instance.bind_vertex_uniform(uniform::Index::from(4), material);
+/// ```
+pub trait IntoUniform {
+    /// The registers making up the uniform, in order. This should be one of
+    /// [`FVec4`], `[FVec4; 2]`, `[FVec4; 3]`, `[FVec4; 4]` or [`Matrix4`].
+    type Registers: Into<Uniform>;
+
+    /// Convert `self` into the registers making up the uniform.
+    fn into_registers(self) -> Self::Registers;
+}
+
+impl<T: IntoUniform> From<T> for Uniform {
+    fn from(value: T) -> Self {
+        value.into_registers().into()
+    }
+}
+
 impl From<Matrix4> for Uniform {
     fn from(value: Matrix4) -> Self {
         Self::Float4(value)
     }
 }
+impl From<[FVec4; 4]> for Uniform {
+    fn from(value: [FVec4; 4]) -> Self {
+        Self::Float4(Matrix4::from_rows(value))
+    }
+}
+
 impl From<[FVec4; 3]> for Uniform {
     fn from(value: [FVec4; 3]) -> Self {
         Self::Float3(value)
@@ -173,3 +433,46 @@ impl From<glam::Mat4> for Uniform {
         Self::Float4(value.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_range_bounds() {
+        let range = IndexRange::new(Index(4), 4).unwrap();
+        assert_eq!(range.end(), Index(8));
+        assert!(range.contains(Index(7)));
+        assert!(!range.contains(Index(8)));
+        assert_eq!(range.get(3), Some(Index(7)));
+        assert_eq!(range.get(4), None);
+        assert_eq!(range.iter().count(), 4);
+
+        assert!(IndexRange::new(Index(0xFF), 1).is_none());
+    }
+
+    #[test]
+    fn index_range_stays_within_kind() {
+        // The last float registers, not spilling into the integer ones
+        assert!(IndexRange::new(Index(0x5C), 4).is_some());
+        assert!(IndexRange::new(Index(0x5E), 4).is_none());
+        assert_eq!(Index(0x5E).offset(1), Some(Index(0x5F)));
+        assert_eq!(Index(0x5E).offset(2), None);
+
+        assert!(IndexRange::new(Index(0x60), 4).is_some());
+        assert!(IndexRange::new(Index(0x62), 3).is_none());
+        assert!(IndexRange::new(Index(0x64), 1).is_none());
+        assert!(IndexRange::new(Index(0x77), 1).is_some());
+        assert!(IndexRange::new(Index(0x77), 2).is_none());
+    }
+
+    #[test]
+    fn index_range_fits() {
+        let matrix = IndexRange::new(Index(0), 4).unwrap();
+        assert!(matrix.fits(&Uniform::Float4(Matrix4::from_cells_wzyx([0.0; 16]))));
+        assert!(!matrix.fits(&Uniform::Bool(true)));
+
+        let vector = IndexRange::new(Index(0), 1).unwrap();
+        assert!(!vector.fits(&Uniform::Float4(Matrix4::from_cells_wzyx([0.0; 16]))));
+    }
+}